@@ -1,142 +1,134 @@
-use crate::game::audio;
-use rand::Rng;
-use rodio::{source::Source, Decoder, OutputStreamHandle, Sink};
-use std::io::Cursor;
-use std::sync::{Arc, Mutex, PoisonError};
-
-const BACKGROUND_MUSIC: &[u8] = include_bytes!("../../assets/audio/background_music.ogg");
-const GAME_OVER_SOUND: &[u8] = include_bytes!("../../assets/audio/game_over.mp3");
-const FUNNY_GAME_OVER_SOUND: &[u8] = include_bytes!("../../assets/audio/funny_game_over.mp3");
-const PAUSE_SOUND: &[u8] = include_bytes!("../../assets/audio/pause.mp3");
-const LINE_CLEAR_SOUND: &[u8] = include_bytes!("../../assets/audio/line_clear.mp3");
-const TETRIS_LINE_CLEAR_SOUND: &[u8] = include_bytes!("../../assets/audio/tetris_line_clear.mp3");
-const MOVE_SOUND: &[u8] = include_bytes!("../../assets/audio/move.mp3");
-const ROTATE_SOUND: &[u8] = MOVE_SOUND;
-const DROP_SOUND: &[u8] = include_bytes!("../../assets/audio/drop.mp3");
-const LEVEL_UP_SOUND: &[u8] = include_bytes!("../../assets/audio/level_up.mp3");
-const NO_MOVE_SOUND: &[u8] = include_bytes!("../../assets/audio/no_move.mp3");
-const NO_ROTATE_SOUND: &[u8] = NO_MOVE_SOUND;
-
-pub struct AudioPlayer {
-    stream_handle: Arc<OutputStreamHandle>,
-    background_music_sink: Arc<Mutex<Option<Sink>>>,
-    is_effects_muted: Arc<Mutex<bool>>,
+use tetris_core::game::events::{GameEvent, Subscriber};
+#[cfg(feature = "audio")]
+use std::sync::Arc;
+
+#[cfg(feature = "audio")]
+mod rodio_player;
+
+pub trait AudioPlayer: Send + Sync {
+    fn play_background_music(&self);
+    /// `funny` picks the rare joke sound instead of the regular one.
+    fn play_game_over_sound(&self, funny: bool);
+    fn play_pause_sound(&self);
+    fn play_line_clear_sound(&self);
+    fn play_tetris_line_clear_sound(&self);
+    fn play_move_sound(&self);
+    fn play_no_move_sound(&self);
+    fn play_rotate_sound(&self);
+    fn play_no_rotate_sound(&self);
+    fn play_drop_sound(&self);
+    fn play_level_up_sound(&self);
+
+    fn mute_background_music(&mut self);
+    #[allow(dead_code)]
+    fn mute_effects(&mut self);
 }
 
-impl AudioPlayer {
-    pub fn new(stream_handle: Arc<OutputStreamHandle>) -> Self {
-        let background_music_sink = Sink::try_new(&stream_handle)
-            .map(|sink| Arc::new(Mutex::new(Some(sink))))
-            .unwrap_or_else(|_| Arc::new(Mutex::new(None)));
+/// Plays the sounds going with the events of a game.
+pub struct GameSounds {
+    player: Box<dyn AudioPlayer>,
+}
 
-        Self {
-            stream_handle,
-            background_music_sink,
-            is_effects_muted: Arc::new(Mutex::new(false)),
-        }
+impl GameSounds {
+    pub fn new(player: Box<dyn AudioPlayer>) -> Self {
+        GameSounds { player }
     }
+}
 
-    fn play_effect_sound(&self, sound_data: &'static [u8]) {
-        if *self
-            .is_effects_muted
-            .lock()
-            .unwrap_or_else(PoisonError::into_inner)
-        {
-            return;
-        }
-
-        let audio_cursor = Cursor::new(sound_data);
-        if let Ok(source) = Decoder::new(audio_cursor) {
-            if let Ok(sink) = Sink::try_new(&self.stream_handle) {
-                sink.append(source);
-                sink.detach();
+impl Subscriber for GameSounds {
+    fn notify(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::Moved { .. } | GameEvent::Held { .. } => self.player.play_move_sound(),
+            GameEvent::MoveFailed | GameEvent::HoldFailed => self.player.play_no_move_sound(),
+            GameEvent::Rotated { .. } => self.player.play_rotate_sound(),
+            GameEvent::RotateFailed => self.player.play_no_rotate_sound(),
+            GameEvent::HardDropped { .. } => self.player.play_drop_sound(),
+            GameEvent::LinesCleared { count: 4.., .. } => {
+                self.player.play_tetris_line_clear_sound()
+            }
+            GameEvent::LinesCleared { .. } | GameEvent::StackCleared { .. } => {
+                self.player.play_line_clear_sound()
+            }
+            GameEvent::LevelUp { .. } => self.player.play_level_up_sound(),
+            GameEvent::Paused => {
+                self.player.play_pause_sound();
+                self.player.mute_background_music();
+            }
+            GameEvent::Resumed => {
+                self.player.play_pause_sound();
+                self.player.play_background_music();
+            }
+            GameEvent::GameOver { funny } => {
+                self.player.mute_background_music();
+                self.player.play_game_over_sound(*funny);
             }
+            GameEvent::Cleared => {
+                self.player.mute_background_music();
+                self.player.play_level_up_sound();
+            }
+            GameEvent::Reset { .. } => self.player.mute_background_music(),
+            GameEvent::PieceSpawned { .. }
+            | GameEvent::Fell { .. }
+            | GameEvent::Locked { .. }
+            | GameEvent::GarbageAdded { .. } => {}
         }
     }
 }
 
-impl audio::AudioPlayer for AudioPlayer {
-    fn play_background_music(&self) {
-        let Some(mut optional_sink_guard) = self.background_music_sink.lock().ok() else {
-            return;
-        };
-        let Some(sink) = optional_sink_guard.as_mut() else {
-            return;
-        };
+/// Loudness of the music and of the sound effects, from 0.0 for silence to 1.0 for full volume.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Volumes {
+    pub music: f32,
+    pub effects: f32,
+}
 
-        if sink.empty() {
-            let audio_cursor = Cursor::new(BACKGROUND_MUSIC);
-            let Ok(source) = Decoder::new(audio_cursor) else {
-                return;
-            };
-            sink.append(source.repeat_infinite());
+impl Default for Volumes {
+    fn default() -> Self {
+        Volumes {
+            music: 1.0,
+            effects: 1.0,
         }
-        sink.play();
-    }
-
-    fn play_game_over_sound(&self) {
-        let mut rng = rand::rng();
-        let use_funny_sound = rng.random_bool(0.01);
-
-        let sound_to_play = if use_funny_sound {
-            FUNNY_GAME_OVER_SOUND
-        } else {
-            GAME_OVER_SOUND
-        };
-        self.play_effect_sound(sound_to_play);
-    }
-
-    fn play_pause_sound(&self) {
-        self.play_effect_sound(PAUSE_SOUND);
-    }
-
-    fn play_line_clear_sound(&self) {
-        self.play_effect_sound(LINE_CLEAR_SOUND);
-    }
-
-    fn play_tetris_line_clear_sound(&self) {
-        self.play_effect_sound(TETRIS_LINE_CLEAR_SOUND);
-    }
-
-    fn play_move_sound(&self) {
-        self.play_effect_sound(MOVE_SOUND);
-    }
-
-    fn play_no_move_sound(&self) {
-        self.play_effect_sound(NO_MOVE_SOUND);
-    }
-
-    fn play_rotate_sound(&self) {
-        self.play_effect_sound(ROTATE_SOUND);
-    }
-
-    fn play_no_rotate_sound(&self) {
-        self.play_effect_sound(NO_ROTATE_SOUND);
-    }
-
-    fn play_drop_sound(&self) {
-        self.play_effect_sound(DROP_SOUND);
     }
+}
 
-    fn play_level_up_sound(&self) {
-        self.play_effect_sound(LEVEL_UP_SOUND);
-    }
+/// Plays nothing, for when there is no audio device or the player turned the sound off.
+pub struct NullAudioPlayer;
+
+impl AudioPlayer for NullAudioPlayer {
+    fn play_background_music(&self) {}
+    fn play_game_over_sound(&self, _funny: bool) {}
+    fn play_pause_sound(&self) {}
+    fn play_line_clear_sound(&self) {}
+    fn play_tetris_line_clear_sound(&self) {}
+    fn play_move_sound(&self) {}
+    fn play_no_move_sound(&self) {}
+    fn play_rotate_sound(&self) {}
+    fn play_no_rotate_sound(&self) {}
+    fn play_drop_sound(&self) {}
+    fn play_level_up_sound(&self) {}
+
+    fn mute_background_music(&mut self) {}
+    fn mute_effects(&mut self) {}
+}
 
-    fn mute_background_music(&mut self) {
-        if let Ok(mut guard) = self.background_music_sink.lock() {
-            if let Some(sink) = guard.as_mut() {
-                if sink.is_paused() {
-                    sink.play();
-                } else {
-                    sink.pause();
-                }
-            }
-        }
-    }
+/// Keeps the audio device open, the sounds stop once it is dropped.
+#[cfg(feature = "audio")]
+pub type AudioOutput = rodio::OutputStream;
+/// Builds without audio support have no device to keep open.
+#[cfg(not(feature = "audio"))]
+pub type AudioOutput = ();
+
+/// Opens the default audio device, failing if there is none or the game was built without
+/// the `audio` feature.
+#[cfg(feature = "audio")]
+pub fn open(volumes: Volumes) -> Result<(AudioOutput, Box<dyn AudioPlayer>), String> {
+    let (output_stream, stream_handle) =
+        rodio::OutputStream::try_default().map_err(|error| error.to_string())?;
+    let player = rodio_player::RodioAudioPlayer::new(Arc::new(stream_handle), volumes);
+    Ok((output_stream, Box::new(player)))
+}
 
-    fn mute_effects(&mut self) {
-        if let Ok(mut is_muted) = self.is_effects_muted.lock() {
-            *is_muted = !*is_muted;
-        }
-    }
+#[cfg(not(feature = "audio"))]
+pub fn open(_volumes: Volumes) -> Result<(AudioOutput, Box<dyn AudioPlayer>), String> {
+    Err("built without audio support".to_string())
 }
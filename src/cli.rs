@@ -0,0 +1,508 @@
+use tetris_core::game::consts::field::{
+    MAX_FIELD_BUFFER, MAX_FIELD_HEIGHT, MAX_FIELD_WIDTH, MIN_FIELD_HEIGHT, MIN_FIELD_WIDTH,
+};
+use tetris_core::game::mode::ModeKind;
+use tetris_core::game::rules::{Rules, MAX_PREVIEW_SIZE};
+use crate::audio::Volumes;
+use crate::config::{load_piece_set, Config};
+use crate::input::InputSettings;
+use crate::renderer::RenderSettings;
+use rand::Rng;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: tetris-console-game [COMMAND] [OPTIONS]
+
+Commands:
+  play              Play a game, the default when no command is given
+  replay <file>     Watch a recorded game
+  scores            Print the high score tables
+  bot               Let a bot play without showing the games
+  bench             Measure how fast the engine plays frames
+
+Play options:
+  --mode <name>         marathon, sprint, ultra, dig, endless or zen, chosen on a menu if not given
+  --seed <number>       Seed deciding the pieces, random if not given
+  --randomizer <name>   weighted, 7-bag, 14-bag, random, nes or tgm
+  --piece-set <name>    tetromino, tromino, pentomino or a piece set file, tetromino by default
+  --gravity <name>      guideline, nes, 20g or off
+  --level <number>      Level to start on
+  --preview <pieces>    Pieces shown in the preview queue, from 1 to 6
+  --width <cells>       Width of the field, from 4 to 40, 10 by default
+  --height <rows>       Visible height of the field, from 4 to 40, 20 by default
+  --buffer <rows>       Hidden rows above the field the pieces spawn in, up to 20, 2 by default
+  --das <ms>            Delayed Auto Shift
+  --arr <ms>            Auto Repeat Rate
+  --sdf <factor>        Soft Drop Factor
+  --theme <name>        classic, guideline or monochrome
+  --no-audio            Play without music and sound effects
+  --record <file>       Save a replay of the last game played
+  --config <file>       Config file to read instead of the default one
+
+Replay options:
+  --verify              Check the replay ends with its recorded result instead of showing it
+  --theme <name>, --config <file>
+
+Scores options:
+  --mode <name>         Only print the table of this mode
+
+Bot options:
+  --games <number>      Games to play, 1 by default
+  --pieces <number>     Pieces to play per game at most, 1000 by default
+  --record <file>       Save a replay of the best game
+  --mode, --seed, --randomizer, --piece-set, --gravity, --level, --preview, --width,
+  --height, --buffer, --config
+
+Bench options:
+  --frames <number>     Frames to play, 100000 by default
+  --mode, --seed, --randomizer, --piece-set, --gravity, --level, --preview, --width,
+  --height, --buffer, --config
+
+  -h, --help            Print this help
+  -V, --version         Print the version
+
+Exit codes: 0 on success, 1 if a file cannot be used or a replay does not verify,
+2 for invalid arguments or config files.";
+
+pub struct PlayOptions {
+    pub seed: u64,
+    /// Mode to play, asked for on the mode select screen if not given
+    pub mode: Option<ModeKind>,
+    pub rules: Rules,
+    pub input: InputSettings,
+    pub render: RenderSettings,
+    pub volumes: Volumes,
+    pub audio: bool,
+    /// File to save a replay of the last game played to
+    pub record: Option<PathBuf>,
+}
+
+pub struct ReplayOptions {
+    pub path: PathBuf,
+    /// Whether to check the replay instead of showing it
+    pub verify: bool,
+    pub render: RenderSettings,
+}
+
+pub struct BotOptions {
+    pub seed: u64,
+    pub rules: Rules,
+    pub games: u32,
+    /// Pieces after which a game is stopped if the bot did not top out
+    pub pieces: u32,
+    /// File to save a replay of the best game to
+    pub record: Option<PathBuf>,
+}
+
+pub struct BenchOptions {
+    pub seed: u64,
+    pub rules: Rules,
+    pub frames: u64,
+}
+
+pub enum Command {
+    Play(PlayOptions),
+    Replay(ReplayOptions),
+    /// Prints the high scores, of a single mode if given
+    Scores(Option<ModeKind>),
+    Bot(BotOptions),
+    Bench(BenchOptions),
+    Help,
+    Version,
+}
+
+/// Reads the command and its options. Settings not given on the command line come from
+/// the config file given with `--config`, or else from `default_config` if it exists.
+pub fn parse(args: Vec<String>, default_config: &Path) -> Result<Command, String> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(Command::Help);
+    }
+    if args.iter().any(|arg| arg == "-V" || arg == "--version") {
+        return Ok(Command::Version);
+    }
+
+    let mut args = args.into_iter().peekable();
+    let command = match args.peek().map(String::as_str) {
+        Some(arg) if !arg.starts_with('-') => args.next().unwrap_or_default(),
+        _ => "play".to_string(),
+    };
+    let args: Vec<String> = args.collect();
+
+    match command.as_str() {
+        "scores" => return parse_scores(args).map(Command::Scores),
+        "play" | "replay" | "bot" | "bench" => {}
+        _ => return Err(format!("Unknown command '{}'", command)),
+    }
+
+    let config = load_config(&args, default_config)?;
+    match command.as_str() {
+        "play" => parse_play(args, config).map(Command::Play),
+        "replay" => parse_replay(args, config).map(Command::Replay),
+        "bot" => parse_bot(args, config).map(Command::Bot),
+        _ => parse_bench(args, config).map(Command::Bench),
+    }
+}
+
+fn load_config(args: &[String], default_config: &Path) -> Result<Config, String> {
+    match args.iter().position(|arg| arg == "--config") {
+        Some(idx) => {
+            let path = args.get(idx + 1).ok_or("Missing value for --config")?;
+            Config::load(Path::new(path), true)
+        }
+        None => Config::load(default_config, false),
+    }
+}
+
+fn value(name: &str, args: &mut impl Iterator<Item = String>) -> Result<String, String> {
+    args.next().ok_or(format!("Missing value for {}", name))
+}
+
+fn parse_number<T: std::str::FromStr>(
+    name: &str,
+    args: &mut impl Iterator<Item = String>,
+    expected: &str,
+) -> Result<T, String> {
+    let value = value(name, args)?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid {} '{}', expected {}", name, value, expected))
+}
+
+fn parse_millis(name: &str, args: &mut impl Iterator<Item = String>) -> Result<Duration, String> {
+    parse_number(name, args, "milliseconds").map(Duration::from_millis)
+}
+
+/// Reads a number that has to be within the given bounds, such as a dimension of the field.
+fn parse_in_range(
+    name: &str,
+    args: &mut impl Iterator<Item = String>,
+    (min, max): (u16, u16),
+    unit: &str,
+) -> Result<u16, String> {
+    let value = value(name, args)?;
+    value
+        .parse()
+        .ok()
+        .filter(|number| (min..=max).contains(number))
+        .ok_or(format!("Invalid {} '{}', expected {} to {} {}", name, value, min, max, unit))
+}
+
+fn parse_seed(args: &mut impl Iterator<Item = String>) -> Result<u64, String> {
+    parse_number("--seed", args, "a positive number")
+}
+
+/// Reads the options changing the rules, which every command playing games takes.
+/// Returns whether the argument was one of them.
+fn parse_rules_option(
+    arg: &str,
+    args: &mut impl Iterator<Item = String>,
+    rules: &mut Rules,
+) -> Result<bool, String> {
+    match arg {
+        "--randomizer" => rules.randomizer = value(arg, args)?.parse()?,
+        "--piece-set" => rules.pieces = load_piece_set(&value(arg, args)?)?,
+        "--gravity" => rules.gravity = value(arg, args)?.parse()?,
+        "--level" => rules.start_level = parse_number(arg, args, "a number from 0 to 255")?,
+        "--preview" => {
+            let value = value(arg, args)?;
+            rules.preview_size = value
+                .parse()
+                .ok()
+                .filter(|size| (1..=MAX_PREVIEW_SIZE).contains(size))
+                .ok_or(format!(
+                    "Invalid --preview '{}', expected 1 to {} pieces",
+                    value, MAX_PREVIEW_SIZE
+                ))?;
+        }
+        "--width" => {
+            let range = (MIN_FIELD_WIDTH, MAX_FIELD_WIDTH);
+            rules.field_width = parse_in_range(arg, args, range, "cells")?;
+        }
+        "--height" => {
+            let range = (MIN_FIELD_HEIGHT, MAX_FIELD_HEIGHT);
+            rules.field_height = parse_in_range(arg, args, range, "rows")?;
+        }
+        "--buffer" => {
+            rules.field_buffer = parse_in_range(arg, args, (0, MAX_FIELD_BUFFER), "rows")?;
+        }
+        // Already read by `load_config`
+        "--config" => {
+            args.next();
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+/// Checks the rules read from the config and the arguments fit together.
+fn check_rules(rules: &Rules) -> Result<(), String> {
+    let min_width = rules.pieces.get_max_size();
+    if rules.field_width < min_width {
+        return Err(format!(
+            "Invalid --width '{}', the {} pieces need at least {} cells",
+            rules.field_width, rules.pieces, min_width
+        ));
+    }
+    if rules.field_height + rules.field_buffer < min_width {
+        return Err(format!(
+            "Invalid --height '{}', the {} pieces need at least {} rows counting the --buffer",
+            rules.field_height, rules.pieces, min_width
+        ));
+    }
+    Ok(())
+}
+
+fn unknown(arg: &str, command: &str) -> String {
+    format!("Unknown argument '{}' for {}", arg, command)
+}
+
+fn parse_play(args: Vec<String>, config: Config) -> Result<PlayOptions, String> {
+    let mut args = args.into_iter();
+    let mut seed = None;
+    let mut audio = true;
+    let mut record = None;
+    let Config {
+        mut mode,
+        mut rules,
+        mut input,
+        mut render,
+        volumes,
+    } = config;
+
+    while let Some(arg) = args.next() {
+        if parse_rules_option(&arg, &mut args, &mut rules)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--mode" => mode = Some(value(&arg, &mut args)?.parse()?),
+            "--seed" => seed = Some(parse_seed(&mut args)?),
+            "--das" => input.das = parse_millis(&arg, &mut args)?,
+            "--arr" => input.arr = parse_millis(&arg, &mut args)?,
+            "--sdf" => {
+                let value = value(&arg, &mut args)?;
+                input.soft_drop_factor = value
+                    .parse()
+                    .ok()
+                    .filter(|&factor| factor > 0)
+                    .ok_or(format!("Invalid --sdf '{}', expected a factor above 0", value))?;
+            }
+            "--theme" => render.theme = value(&arg, &mut args)?.parse()?,
+            "--no-audio" => audio = false,
+            "--record" => record = Some(value(&arg, &mut args)?.into()),
+            _ => return Err(unknown(&arg, "play")),
+        }
+    }
+
+    check_rules(&rules)?;
+    Ok(PlayOptions {
+        seed: seed.unwrap_or_else(|| rand::rng().random()),
+        mode,
+        rules,
+        input,
+        render,
+        volumes,
+        audio,
+        record,
+    })
+}
+
+fn parse_replay(args: Vec<String>, config: Config) -> Result<ReplayOptions, String> {
+    let mut args = args.into_iter();
+    let mut path = None;
+    let mut verify = false;
+    let mut render = config.render;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--verify" => verify = true,
+            "--theme" => render.theme = value(&arg, &mut args)?.parse()?,
+            "--config" => {
+                args.next();
+            }
+            _ if !arg.starts_with('-') && path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(unknown(&arg, "replay")),
+        }
+    }
+
+    Ok(ReplayOptions {
+        path: path.ok_or("Missing the replay file to watch")?,
+        verify,
+        render,
+    })
+}
+
+fn parse_scores(args: Vec<String>) -> Result<Option<ModeKind>, String> {
+    let mut args = args.into_iter();
+    let mut mode = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--mode" => mode = Some(value(&arg, &mut args)?.parse()?),
+            _ => return Err(unknown(&arg, "scores")),
+        }
+    }
+
+    Ok(mode)
+}
+
+fn parse_bot(args: Vec<String>, config: Config) -> Result<BotOptions, String> {
+    let mut args = args.into_iter();
+    let mut options = BotOptions {
+        seed: rand::rng().random(),
+        rules: Rules {
+            mode: config.mode.unwrap_or_default(),
+            ..config.rules
+        },
+        games: 1,
+        pieces: 1000,
+        record: None,
+    };
+
+    while let Some(arg) = args.next() {
+        if parse_rules_option(&arg, &mut args, &mut options.rules)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--mode" => options.rules.mode = value(&arg, &mut args)?.parse()?,
+            "--seed" => options.seed = parse_seed(&mut args)?,
+            "--games" => options.games = parse_number(&arg, &mut args, "a number of games")?,
+            "--pieces" => options.pieces = parse_number(&arg, &mut args, "a number of pieces")?,
+            "--record" => options.record = Some(value(&arg, &mut args)?.into()),
+            _ => return Err(unknown(&arg, "bot")),
+        }
+    }
+
+    check_rules(&options.rules)?;
+    Ok(options)
+}
+
+fn parse_bench(args: Vec<String>, config: Config) -> Result<BenchOptions, String> {
+    let mut args = args.into_iter();
+    let mut options = BenchOptions {
+        seed: 0,
+        rules: Rules {
+            mode: config.mode.unwrap_or_default(),
+            ..config.rules
+        },
+        frames: 100_000,
+    };
+
+    while let Some(arg) = args.next() {
+        if parse_rules_option(&arg, &mut args, &mut options.rules)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--mode" => options.rules.mode = value(&arg, &mut args)?.parse()?,
+            "--seed" => options.seed = parse_seed(&mut args)?,
+            "--frames" => options.frames = parse_number(&arg, &mut args, "a number of frames")?,
+            _ => return Err(unknown(&arg, "bench")),
+        }
+    }
+
+    check_rules(&options.rules)?;
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use tetris_core::game::gravity::GravityKind;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        let missing = env::temp_dir().join("tetris-cli-test-missing.toml");
+        parse(args.iter().map(|arg| arg.to_string()).collect(), &missing)
+    }
+
+    #[test]
+    fn test_play_is_the_default_command() {
+        let Ok(Command::Play(options)) = parse_args(&["--seed", "42", "--no-audio"]) else {
+            panic!("Expected the play command");
+        };
+        assert_eq!(options.seed, 42);
+        assert!(!options.audio);
+        assert_eq!(options.mode, None);
+
+        let args = ["play", "--level", "5", "--das", "90", "--mode", "sprint"];
+        let Ok(Command::Play(options)) = parse_args(&args) else {
+            panic!("Expected the play command");
+        };
+        assert!(options.audio);
+        assert_eq!(options.mode, Some(ModeKind::Sprint));
+        assert_eq!(options.rules.start_level, 5);
+        assert_eq!(options.input.das, Duration::from_millis(90));
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let Ok(Command::Replay(options)) = parse_args(&["replay", "best.replay", "--verify"]) else {
+            panic!("Expected the replay command");
+        };
+        assert_eq!(options.path, PathBuf::from("best.replay"));
+        assert!(options.verify);
+
+        let args = ["bot", "--games", "3", "--gravity", "nes", "--mode", "marathon"];
+        let Ok(Command::Bot(options)) = parse_args(&args) else {
+            panic!("Expected the bot command");
+        };
+        assert_eq!(options.games, 3);
+        assert_eq!(options.rules.gravity, GravityKind::Nes);
+        assert_eq!(options.rules.mode, ModeKind::Marathon);
+
+        let args = ["bot", "--width", "6", "--height", "40", "--buffer", "0"];
+        let Ok(Command::Bot(options)) = parse_args(&args) else {
+            panic!("Expected the bot command");
+        };
+        assert_eq!(options.rules.field_width, 6);
+        assert_eq!(options.rules.field_height, 40);
+        assert_eq!(options.rules.field_buffer, 0);
+
+        let args = ["bench", "--piece-set", "pentomino", "--width", "5"];
+        let Ok(Command::Bench(options)) = parse_args(&args) else {
+            panic!("Expected the bench command");
+        };
+        assert_eq!(options.rules.pieces.name(), "pentomino");
+        assert_eq!(options.rules.field_width, 5);
+
+        assert!(matches!(parse_args(&["scores"]), Ok(Command::Scores(None))));
+        assert!(matches!(
+            parse_args(&["scores", "--mode", "ultra"]),
+            Ok(Command::Scores(Some(ModeKind::Ultra)))
+        ));
+        assert!(matches!(parse_args(&["bench"]), Ok(Command::Bench(_))));
+        assert!(matches!(parse_args(&["bot", "--help"]), Ok(Command::Help)));
+        assert!(matches!(parse_args(&["-V"]), Ok(Command::Version)));
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let errors = [
+            (vec!["fly"], "Unknown command 'fly'"),
+            (vec!["--verify"], "Unknown argument '--verify' for play"),
+            (vec!["replay"], "Missing the replay file"),
+            (vec!["--mode", "puzzle"], "Unknown mode 'puzzle'"),
+            (vec!["bot", "--games"], "Missing value for --games"),
+            (vec!["--preview", "9"], "Invalid --preview"),
+            (vec!["--width", "41"], "Invalid --width '41', expected 4 to 40 cells"),
+            (vec!["bench", "--buffer", "-1"], "Invalid --buffer"),
+            (vec!["--piece-set", "hexomino"], "Unknown piece set 'hexomino'"),
+            (
+                vec!["--piece-set", "pentomino", "--width", "4"],
+                "Invalid --width '4', the pentomino pieces need at least 5 cells",
+            ),
+            (
+                vec!["bench", "--piece-set", "pentomino", "--height", "4", "--buffer", "0"],
+                "Invalid --height '4', the pentomino pieces need at least 5 rows \
+                 counting the --buffer",
+            ),
+            (vec!["--config", "/nonexistent/config.toml"], "Failed to read the config"),
+        ];
+        for (args, expected) in errors {
+            let Err(error) = parse_args(&args) else {
+                panic!("{:?} should fail", args);
+            };
+            assert!(error.starts_with(expected), "{:?} gave {}", args, error);
+        }
+    }
+}
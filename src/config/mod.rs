@@ -0,0 +1,437 @@
+use tetris_core::game::consts::field::{
+    MAX_FIELD_BUFFER, MAX_FIELD_HEIGHT, MAX_FIELD_WIDTH, MIN_FIELD_HEIGHT, MIN_FIELD_WIDTH,
+};
+use tetris_core::game::gravity::GravityKind;
+use tetris_core::game::mode::ModeKind;
+use tetris_core::game::piece_set::PieceSet;
+use tetris_core::game::randomizer::RandomizerKind;
+use tetris_core::game::rules::{Rules, MAX_PREVIEW_SIZE};
+use crate::audio::Volumes;
+use crate::input::bindings::{parse_key, Action, KeyBindings};
+use crate::input::InputSettings;
+use crate::renderer::theme::Theme;
+use crate::renderer::RenderSettings;
+use crossterm::event::KeyCode;
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Name of the config file in the config directory.
+pub const CONFIG_FILE: &str = "config.toml";
+
+/// Settings read from the config file, which command line arguments may override.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// Mode to play, asked for on the mode select screen if not given.
+    pub mode: Option<ModeKind>,
+    pub rules: Rules,
+    pub input: InputSettings,
+    pub render: RenderSettings,
+    pub volumes: Volumes,
+}
+
+impl Config {
+    /// Reads the config file at the given path. A missing file gives the defaults,
+    /// unless `required` tells it was asked for explicitly.
+    pub fn load(path: &Path, required: bool) -> Result<Config, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound && !required => {
+                return Ok(Config::default());
+            }
+            Err(error) => {
+                return Err(format!("Failed to read the config {}: {}", path.display(), error));
+            }
+        };
+
+        Config::parse(&text)
+            .map_err(|error| format!("Error in the config {}: {}", path.display(), error))
+    }
+
+    /// Reads the TOML text of a config file. Settings left out keep their defaults.
+    /// Errors tell the line and column of the setting at fault.
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let file: ConfigFile = toml::from_str(text).map_err(|error| error.to_string())?;
+        let mut config = Config::default();
+        let ConfigFile {
+            game: GameSection(game),
+            input,
+            keys,
+            visuals,
+            audio,
+        } = file;
+
+        if let Some(Parsed(mode)) = game.mode {
+            config.mode = Some(mode);
+        }
+        if let Some(Parsed(randomizer)) = game.randomizer {
+            config.rules.randomizer = randomizer;
+        }
+        if let Some(PieceSetFile(pieces)) = game.piece_set {
+            config.rules.pieces = pieces;
+        }
+        if let Some(Parsed(gravity)) = game.gravity {
+            config.rules.gravity = gravity;
+        }
+        if let Some(level) = game.level {
+            config.rules.start_level = level;
+        }
+        if let Some(Ranged(preview)) = game.preview {
+            config.rules.preview_size = preview as usize;
+        }
+        if let Some(Ranged(width)) = game.width {
+            config.rules.field_width = width as u16;
+        }
+        if let Some(Ranged(height)) = game.height {
+            config.rules.field_height = height as u16;
+        }
+        if let Some(Ranged(buffer)) = game.buffer {
+            config.rules.field_buffer = buffer as u16;
+        }
+
+        if let Some(das) = input.das {
+            config.input.das = Duration::from_millis(das);
+        }
+        if let Some(arr) = input.arr {
+            config.input.arr = Duration::from_millis(arr);
+        }
+        if let Some(Ranged(sdf)) = input.sdf {
+            config.input.soft_drop_factor = sdf as u32;
+        }
+
+        config.render.bindings = keys.0;
+        if let Some(ghost) = visuals.ghost {
+            config.render.show_ghost = ghost;
+        }
+        if let Some(preview) = visuals.preview {
+            config.render.show_preview = preview;
+        }
+        if let Some(Parsed(theme)) = visuals.theme {
+            config.render.theme = theme;
+        }
+
+        if let Some(Ranged(volume)) = audio.music_volume {
+            config.volumes.music = volume as f32 / 100.0;
+        }
+        if let Some(Ranged(volume)) = audio.effects_volume {
+            config.volumes.effects = volume as f32 / 100.0;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Layout of the config file, one table per section.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    game: GameSection,
+    input: InputSection,
+    keys: KeysSection,
+    visuals: VisualsSection,
+    audio: AudioSection,
+}
+
+/// The game table, with a field large enough for the pieces.
+#[derive(Default, Deserialize)]
+#[serde(try_from = "GameTable")]
+struct GameSection(GameTable);
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GameTable {
+    mode: Option<Parsed<ModeKind>>,
+    randomizer: Option<Parsed<RandomizerKind>>,
+    piece_set: Option<PieceSetFile>,
+    gravity: Option<Parsed<GravityKind>>,
+    level: Option<u8>,
+    preview: Option<Ranged<1, { MAX_PREVIEW_SIZE as u64 }>>,
+    width: Option<Ranged<{ MIN_FIELD_WIDTH as u64 }, { MAX_FIELD_WIDTH as u64 }>>,
+    height: Option<Ranged<{ MIN_FIELD_HEIGHT as u64 }, { MAX_FIELD_HEIGHT as u64 }>>,
+    buffer: Option<Ranged<0, { MAX_FIELD_BUFFER as u64 }>>,
+}
+
+impl TryFrom<GameTable> for GameSection {
+    type Error = String;
+
+    fn try_from(game: GameTable) -> Result<Self, Self::Error> {
+        let rules = Rules::default();
+        let pieces = game.piece_set.as_ref().map_or(&rules.pieces, |PieceSetFile(pieces)| pieces);
+        let width = game.width.as_ref().map_or(rules.field_width as u64, |size| size.0);
+        let height = game.height.as_ref().map_or(rules.field_height as u64, |size| size.0);
+        let buffer = game.buffer.as_ref().map_or(rules.field_buffer as u64, |size| size.0);
+
+        let min_size = pieces.get_max_size() as u64;
+        if width < min_size {
+            return Err(format!(
+                "Invalid width '{}', the {} pieces need at least {} cells",
+                width, pieces, min_size
+            ));
+        }
+        if height + buffer < min_size {
+            return Err(format!(
+                "Invalid height '{}', the {} pieces need at least {} rows counting the buffer",
+                height, pieces, min_size
+            ));
+        }
+        Ok(GameSection(game))
+    }
+}
+
+/// Timings in milliseconds, as taken by `--das`, `--arr` and `--sdf`.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct InputSection {
+    das: Option<u64>,
+    arr: Option<u64>,
+    sdf: Option<Ranged<1, { u32::MAX as u64 }>>,
+}
+
+/// Keys of the actions named in the file, each replacing the default keys of its action.
+#[derive(Default, Deserialize)]
+#[serde(try_from = "BTreeMap<Parsed<Action>, Vec<Key>>")]
+struct KeysSection(KeyBindings);
+
+impl TryFrom<BTreeMap<Parsed<Action>, Vec<Key>>> for KeysSection {
+    type Error = String;
+
+    fn try_from(keys: BTreeMap<Parsed<Action>, Vec<Key>>) -> Result<Self, Self::Error> {
+        let bindings: Vec<(Action, Vec<KeyCode>)> = keys
+            .into_iter()
+            .map(|(Parsed(action), keys)| {
+                (action, keys.into_iter().map(|Key(code)| code).collect())
+            })
+            .collect();
+        KeyBindings::default().rebind(&bindings).map(KeysSection)
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct VisualsSection {
+    ghost: Option<bool>,
+    preview: Option<bool>,
+    theme: Option<Parsed<Theme>>,
+}
+
+/// Volumes in percent.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct AudioSection {
+    music_volume: Option<Ranged<0, 100>>,
+    effects_volume: Option<Ranged<0, 100>>,
+}
+
+/// A value written as text and read with its `FromStr` implementation.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Parsed<T>(T);
+
+impl<'de, T: FromStr<Err = String>> Deserialize<'de> for Parsed<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map(Parsed).map_err(de::Error::custom)
+    }
+}
+
+/// A whole number from `MIN` to `MAX`.
+struct Ranged<const MIN: u64, const MAX: u64>(u64);
+
+impl<'de, const MIN: u64, const MAX: u64> Deserialize<'de> for Ranged<MIN, MAX> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = u64::deserialize(deserializer)?;
+        if !(MIN..=MAX).contains(&value) {
+            return Err(de::Error::custom(format!(
+                "expected a number from {} to {}, found {}",
+                MIN, MAX, value
+            )));
+        }
+        Ok(Ranged(value))
+    }
+}
+
+/// A piece set, named or read from the file at the given path.
+struct PieceSetFile(Arc<PieceSet>);
+
+impl<'de> Deserialize<'de> for PieceSetFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        load_piece_set(&value).map(PieceSetFile).map_err(de::Error::custom)
+    }
+}
+
+/// Reads one of the piece sets the game ships with by its name, or else the piece set file at
+/// the given path. Custom sets are named after their file.
+pub fn load_piece_set(value: &str) -> Result<Arc<PieceSet>, String> {
+    if let Some(pieces) = PieceSet::builtin(value) {
+        return Ok(pieces);
+    }
+
+    let path = Path::new(value);
+    let source = fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Unknown piece set '{}', expected one of: {} or a piece set file ({})",
+            value,
+            PieceSet::BUILTIN.join(", "),
+            error
+        )
+    })?;
+    let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or(value);
+    PieceSet::parse(name, &source)
+        .map(Arc::new)
+        .map_err(|error| format!("Error in the piece set {}: {}", path.display(), error))
+}
+
+struct Key(KeyCode);
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        parse_key(&name).map(Key).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_parse_reads_every_section() {
+        let text = r#"
+            [game]
+            mode = "sprint"
+            randomizer = "7-bag"
+            piece_set = "pentomino"
+            gravity = "nes"
+            level = 0
+            preview = 3
+            width = 20
+            height = 40
+            buffer = 4
+
+            [input]
+            das = 100
+            arr = 0
+            sdf = 40
+
+            [keys]
+            move_left = ["a", "Left"]
+            hold = ["c", "Space"]
+            pause = ["p"]
+
+            [visuals]
+            ghost = false
+            preview = false
+            theme = "monochrome"
+
+            [audio]
+            music_volume = 50
+            effects_volume = 0
+        "#;
+        let config = Config::parse(text).unwrap();
+
+        assert_eq!(config.mode, Some(ModeKind::Sprint));
+        assert_eq!(config.rules.randomizer, RandomizerKind::SevenBag);
+        assert_eq!(config.rules.pieces, PieceSet::builtin("pentomino").unwrap());
+        assert_eq!(config.rules.gravity, GravityKind::Nes);
+        assert_eq!(config.rules.start_level, 0);
+        assert_eq!(config.rules.preview_size, 3);
+        assert_eq!(config.rules.field_width, 20);
+        assert_eq!(config.rules.field_height, 40);
+        assert_eq!(config.rules.field_buffer, 4);
+        assert_eq!(config.input.das, Duration::from_millis(100));
+        assert_eq!(config.input.arr, Duration::ZERO);
+        assert_eq!(config.input.soft_drop_factor, 40);
+
+        let bindings = &config.render.bindings;
+        assert_eq!(bindings.action_for(KeyCode::Left), Some(Action::MoveLeft));
+        assert_eq!(bindings.action_for(KeyCode::Char(' ')), Some(Action::Hold));
+        assert_eq!(bindings.action_for(KeyCode::Char('q')), None);
+        assert_eq!(bindings.action_for(KeyCode::Char('w')), Some(Action::HardDrop));
+
+        assert!(!config.render.show_ghost);
+        assert!(!config.render.show_preview);
+        assert_eq!(config.render.theme, Theme::MONOCHROME);
+        assert_eq!(config.volumes.music, 0.5);
+        assert_eq!(config.volumes.effects, 0.0);
+    }
+
+    #[test]
+    fn test_missing_settings_keep_defaults() {
+        let config = Config::parse("[visuals]\nghost = false\n").unwrap();
+        assert_eq!(config.mode, None);
+        assert_eq!(config.rules, Rules::default());
+        assert_eq!(config.input, InputSettings::default());
+        assert_eq!(config.render.bindings, KeyBindings::default());
+        assert!(config.render.show_preview);
+    }
+
+    #[test]
+    fn test_errors_tell_the_line() {
+        let cases = [
+            ("[game]\nlevel = 300\n", "line 2"),
+            ("[game]\n\npreview = 9\n", "expected a number from 1 to 6"),
+            ("[game]\nwidth = 2\n", "expected a number from 4 to 40"),
+            ("[input]\ndas = -5\n", "line 2"),
+            ("[keys]\nhold = [\"shift\"]\n", "Unknown key 'shift'"),
+            ("[keys]\njump = [\"j\"]\n", "Unknown action 'jump'"),
+            ("[keys]\nhold = [\"x\"]\npause = [\"x\"]\n", "bound to both"),
+            ("[visuals]\ntheme = \"neon\"\n", "Unknown theme 'neon'"),
+            ("[game]\nmode = \"puzzle\"\n", "Unknown mode 'puzzle'"),
+            ("[game]\npiece_set = \"/nonexistent.txt\"\n", "Unknown piece set"),
+            ("[game]\npiece_set = \"pentomino\"\nwidth = 4\n", "need at least 5 cells"),
+            ("[audio]\nvolume = 10\n", "unknown field `volume`"),
+            ("[game\n", "line 1"),
+        ];
+        for (text, expected) in cases {
+            let error = Config::parse(text).unwrap_err();
+            assert!(error.contains(expected), "{:?} gave {}", text, error);
+            assert!(error.contains("line"), "{:?} gave {}", text, error);
+        }
+    }
+
+    #[test]
+    fn test_field_fits_the_pieces() {
+        let text = "[game]\npiece_set = \"pentomino\"\nheight = 4\nbuffer = 0\n";
+        let error = Config::parse(text).unwrap_err();
+        assert!(error.ends_with(
+            "Invalid height '4', the pentomino pieces need at least 5 rows counting the buffer\n"
+        ));
+
+        let text = "[game]\npiece_set = \"pentomino\"\nwidth = 4\n";
+        let error = Config::parse(text).unwrap_err();
+        assert!(error.ends_with("Invalid width '4', the pentomino pieces need at least 5 cells\n"));
+        assert!(Config::parse("[game]\npiece_set = \"pentomino\"\nwidth = 5\n").is_ok());
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let path = env::temp_dir().join(format!("tetris-config-test-{}.toml", std::process::id()));
+        assert!(Config::load(&path, false).is_ok());
+
+        let error = Config::load(&path, true).unwrap_err();
+        assert!(error.starts_with("Failed to read the config"));
+    }
+
+    #[test]
+    fn test_load_piece_set_file() {
+        let path = env::temp_dir().join(format!("tetris-pieces-test-{}.txt", std::process::id()));
+        fs::write(&path, "piece dot red\nX\n").unwrap();
+        let pieces = load_piece_set(path.to_str().unwrap()).unwrap();
+        assert!(pieces.name().starts_with("tetris-pieces-test-"));
+        assert_eq!(pieces.get_count(), 1);
+
+        fs::write(&path, "piece dot pink\nX\n").unwrap();
+        let error = load_piece_set(path.to_str().unwrap()).unwrap_err();
+        assert!(error.starts_with("Error in the piece set"), "{}", error);
+        assert!(error.ends_with("Line 1: Unknown color 'pink', expected red, green, yellow, \
+                                 blue, magenta, cyan, white, orange, grey or #rrggbb"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,123 @@
+use tetris_core::game::bot::Bot;
+use tetris_core::game::input::Input;
+use tetris_core::game::rendering::NullRenderer;
+use tetris_core::game::replay::Replay;
+use tetris_core::game::{Game, FRAME_DURATION, FRAMES_PER_SECOND};
+use crate::cli::{BenchOptions, BotOptions};
+use crate::scores::{format_duration, format_row, table_header, HighScores, SCORES_FILE};
+use crate::storage;
+use std::fs;
+use std::time::Instant;
+
+/// Lets the bot play the games without showing them, printing the result of each one.
+/// Returns the exit code, 1 if the replay of the best game could not be saved.
+pub fn run_bot(options: &BotOptions) -> i32 {
+    let mut game = Game::new(Box::new(NullRenderer), options.rules.clone(), options.seed);
+    let mut best: Option<Replay> = None;
+
+    for number in 1..=options.games {
+        if number > 1 {
+            game.reset();
+        }
+        game.set_paused(false);
+        let seed = game.get_seed();
+        let mut bot = Bot::default();
+        let mut pieces = 0;
+
+        while !game.is_game_over() && pieces < options.pieces {
+            let input = bot.next_input(&game);
+            if input == Input::HardDrop {
+                pieces += 1;
+            }
+            game.apply(input);
+            game.update(FRAME_DURATION);
+        }
+
+        let outcome = if game.is_cleared() {
+            ", cleared"
+        } else if game.is_game_over() {
+            ", topped out"
+        } else {
+            ""
+        };
+        println!(
+            "Game {} (seed {}): score {}, {} lines, level {}, {} pieces in {}{}",
+            number,
+            seed,
+            game.score,
+            game.lines,
+            game.level,
+            pieces,
+            format_duration(game.get_time()),
+            outcome
+        );
+
+        if best.as_ref().is_none_or(|replay| game.score > replay.result.score) {
+            best = Some(game.replay());
+        }
+    }
+
+    if let (Some(path), Some(replay)) = (&options.record, best) {
+        if let Err(error) = fs::write(path, replay.to_bytes()) {
+            eprintln!("Failed to save the replay to {}: {}", path.display(), error);
+            return 1;
+        }
+        println!("Saved the best game to {}", path.display());
+    }
+    0
+}
+
+/// Plays frames with the bot as fast as possible, starting over whenever it tops out,
+/// and prints how many frames were played per second.
+pub fn run_bench(options: &BenchOptions) {
+    let mut game = Game::new(Box::new(NullRenderer), options.rules.clone(), options.seed);
+    game.set_paused(false);
+    let mut bot = Bot::default();
+    let mut games = 1;
+
+    let start = Instant::now();
+    for _ in 0..options.frames {
+        if game.is_game_over() {
+            game.reset();
+            game.set_paused(false);
+            bot = Bot::default();
+            games += 1;
+        }
+        game.apply(bot.next_input(&game));
+        game.update(FRAME_DURATION);
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let frames_per_second = options.frames as f64 / elapsed.max(f64::EPSILON);
+    println!(
+        "Played {} frames over {} games in {:.3}s: {:.0} frames/s, {:.0}x real time",
+        options.frames,
+        games,
+        elapsed,
+        frames_per_second,
+        frames_per_second / FRAMES_PER_SECOND as f64
+    );
+}
+
+/// Prints the high score tables, or only the one of the given mode.
+pub fn print_scores(mode: Option<&str>) {
+    let scores = HighScores::load(storage::data_dir().join(SCORES_FILE));
+    let modes: Vec<&str> = match mode {
+        Some(mode) => vec![mode],
+        None => scores.get_modes().collect(),
+    };
+
+    if modes.iter().all(|mode| scores.get_table(mode).is_empty()) {
+        println!("No high scores yet");
+        return;
+    }
+
+    for mode in modes {
+        println!("High scores - {}", mode);
+        println!("{}", table_header(mode, true));
+        for (rank, high_score) in scores.get_table(mode).iter().enumerate() {
+            println!("{}", format_row(mode, rank, high_score, true));
+        }
+        println!();
+    }
+}
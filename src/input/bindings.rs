@@ -0,0 +1,319 @@
+use super::Shift;
+use tetris_core::game::input::Input;
+use crossterm::event::KeyCode;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Something the player can do with a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    RotateCw,
+    RotateCcw,
+    Rotate180,
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    Hold,
+    Pause,
+    Restart,
+    HighScores,
+}
+
+impl Action {
+    /// In the order the controls are listed in.
+    pub const ALL: [Action; 11] = [
+        Action::RotateCw,
+        Action::RotateCcw,
+        Action::Rotate180,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::SoftDrop,
+        Action::HardDrop,
+        Action::Hold,
+        Action::Pause,
+        Action::Restart,
+        Action::HighScores,
+    ];
+
+    /// Name of the action in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::RotateCw => "rotate_cw",
+            Action::RotateCcw => "rotate_ccw",
+            Action::Rotate180 => "rotate_180",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::SoftDrop => "soft_drop",
+            Action::HardDrop => "hard_drop",
+            Action::Hold => "hold",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+            Action::HighScores => "high_scores",
+        }
+    }
+
+    /// What the action does, as listed in the pause screen.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::RotateCw => "rotate right",
+            Action::RotateCcw => "rotate left",
+            Action::Rotate180 => "rotate 180°",
+            Action::MoveLeft => "move left",
+            Action::MoveRight => "move right",
+            Action::SoftDrop => "move down",
+            Action::HardDrop => "drop down",
+            Action::Hold => "hold",
+            Action::Pause => "pause / resume",
+            Action::Restart => "restart",
+            Action::HighScores => "high scores",
+        }
+    }
+
+    /// Movement repeated while the key is held down.
+    pub fn shift(&self) -> Option<Shift> {
+        match self {
+            Action::MoveLeft => Some(Shift::Left),
+            Action::MoveRight => Some(Shift::Right),
+            Action::SoftDrop => Some(Shift::Down),
+            _ => None,
+        }
+    }
+
+    /// Input applied to the game once per key press.
+    pub fn input(&self) -> Option<Input> {
+        match self {
+            Action::RotateCw => Some(Input::RotateCw),
+            Action::RotateCcw => Some(Input::RotateCcw),
+            Action::Rotate180 => Some(Input::Rotate180),
+            Action::HardDrop => Some(Input::HardDrop),
+            Action::Hold => Some(Input::Hold),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Action::ALL.iter().map(|action| action.name()).collect();
+                format!("Unknown action '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Names of the keys that are not a single character.
+const KEY_NAMES: [(KeyCode, &str); 15] = [
+    (KeyCode::Char(' '), "Space"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Esc, "Esc"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+];
+
+/// Reads a key name such as `a`, `Left`, `Space` or `F5`, ignoring case.
+pub fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next())
+        && !c.is_whitespace()
+    {
+        return Ok(KeyCode::Char(c.to_ascii_lowercase()));
+    }
+
+    let lowercase = name.to_ascii_lowercase();
+    if let Some(number) = lowercase.strip_prefix('f').and_then(|n| n.parse().ok())
+        && (1..=12).contains(&number)
+    {
+        return Ok(KeyCode::F(number));
+    }
+    if lowercase == "escape" {
+        return Ok(KeyCode::Esc);
+    }
+
+    KEY_NAMES
+        .iter()
+        .find(|(_, key_name)| key_name.eq_ignore_ascii_case(name))
+        .map(|&(code, _)| code)
+        .ok_or_else(|| {
+            format!(
+                "Unknown key '{}', expected a single character, F1 to F12 or one of: {}",
+                name,
+                KEY_NAMES.map(|(_, key_name)| key_name).join(", ")
+            )
+        })
+}
+
+/// Name of the key as shown to the player, which `parse_key` reads back.
+pub fn key_name(code: KeyCode) -> String {
+    if let Some((_, name)) = KEY_NAMES.iter().find(|(key, _)| *key == code) {
+        return name.to_string();
+    }
+
+    match code {
+        KeyCode::Char(c) => c.to_uppercase().to_string(),
+        KeyCode::F(number) => format!("F{}", number),
+        _ => format!("{:?}", code),
+    }
+}
+
+/// Keys bound to each action. A key triggers a single action, an action may have many keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    keys: Vec<(KeyCode, Action)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            keys: vec![
+                (KeyCode::Char('r'), Action::RotateCw),
+                (KeyCode::Char('e'), Action::RotateCcw),
+                (KeyCode::Char('f'), Action::Rotate180),
+                (KeyCode::Char('a'), Action::MoveLeft),
+                (KeyCode::Char('d'), Action::MoveRight),
+                (KeyCode::Char('s'), Action::SoftDrop),
+                (KeyCode::Char('w'), Action::HardDrop),
+                (KeyCode::Char('q'), Action::Hold),
+                (KeyCode::Char(' '), Action::Pause),
+                (KeyCode::Backspace, Action::Restart),
+                (KeyCode::Char('h'), Action::HighScores),
+            ],
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Replaces the keys of the given actions. The other actions keep the keys that are not
+    /// taken by the new bindings. Fails if a key is given to two actions.
+    pub fn rebind(&self, bindings: &[(Action, Vec<KeyCode>)]) -> Result<Self, String> {
+        let mut keys: Vec<(KeyCode, Action)> = Vec::new();
+        for (action, codes) in bindings {
+            for &code in codes {
+                match keys.iter().find(|(key, _)| *key == code) {
+                    Some((_, other)) if other == action => {}
+                    Some((_, other)) => {
+                        return Err(format!(
+                            "Key '{}' is bound to both {} and {}",
+                            key_name(code),
+                            other,
+                            action
+                        ));
+                    }
+                    None => keys.push((code, *action)),
+                }
+            }
+        }
+
+        for &(code, action) in &self.keys {
+            let rebound = bindings.iter().any(|(other, _)| *other == action);
+            let taken = keys.iter().any(|(key, _)| *key == code);
+            if !rebound && !taken {
+                keys.push((code, action));
+            }
+        }
+
+        Ok(KeyBindings { keys })
+    }
+
+    pub fn action_for(&self, code: KeyCode) -> Option<Action> {
+        self.keys
+            .iter()
+            .find(|(key, _)| *key == code)
+            .map(|&(_, action)| action)
+    }
+
+    pub fn get_keys(&self, action: Action) -> impl Iterator<Item = KeyCode> + '_ {
+        self.keys
+            .iter()
+            .filter(move |(_, other)| *other == action)
+            .map(|&(code, _)| code)
+    }
+
+    /// Names of the keys of the action joined by slashes, such as `A/Left`.
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<String> = self.get_keys(action).map(key_name).collect();
+        names.join("/")
+    }
+
+    /// Keys and description of every action that has keys, in the order they are listed in.
+    pub fn controls_text(&self) -> Vec<(String, &'static str)> {
+        Action::ALL
+            .into_iter()
+            .map(|action| (self.describe(action), action.description()))
+            .filter(|(keys, _)| !keys.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_names_round_trip() {
+        for code in KeyBindings::default().keys.iter().map(|&(code, _)| code) {
+            assert_eq!(parse_key(&key_name(code)), Ok(code));
+        }
+        assert_eq!(parse_key("LEFT"), Ok(KeyCode::Left));
+        assert_eq!(parse_key("escape"), Ok(KeyCode::Esc));
+        assert_eq!(parse_key("f12"), Ok(KeyCode::F(12)));
+        assert_eq!(parse_key("pagedown"), Ok(KeyCode::PageDown));
+        assert_eq!(parse_key("X"), Ok(KeyCode::Char('x')));
+        assert!(parse_key("f13").is_err());
+        assert!(parse_key("ctrl").is_err());
+        assert!(parse_key("").is_err());
+    }
+
+    #[test]
+    fn test_rebind_keeps_free_default_keys() {
+        let bindings = KeyBindings::default()
+            .rebind(&[
+                (Action::MoveLeft, vec![KeyCode::Char('a'), KeyCode::Left]),
+                (Action::RotateCcw, vec![KeyCode::Char('q')]),
+            ])
+            .unwrap();
+
+        assert_eq!(bindings.action_for(KeyCode::Left), Some(Action::MoveLeft));
+        assert_eq!(bindings.action_for(KeyCode::Char('q')), Some(Action::RotateCcw));
+        assert_eq!(bindings.action_for(KeyCode::Char('e')), None);
+        assert_eq!(bindings.get_keys(Action::Hold).count(), 0);
+        assert_eq!(bindings.describe(Action::MoveLeft), "A/Left");
+        assert_eq!(bindings.action_for(KeyCode::Char('w')), Some(Action::HardDrop));
+        assert!(!bindings
+            .controls_text()
+            .iter()
+            .any(|(_, description)| *description == "hold"));
+    }
+
+    #[test]
+    fn test_rebind_rejects_key_used_twice() {
+        let error = KeyBindings::default()
+            .rebind(&[
+                (Action::Hold, vec![KeyCode::Char('c')]),
+                (Action::Rotate180, vec![KeyCode::Char('c')]),
+            ])
+            .unwrap_err();
+        assert_eq!(error, "Key 'C' is bound to both hold and rotate_180");
+    }
+}
@@ -0,0 +1,325 @@
+use std::time::{Duration, Instant};
+
+pub mod bindings;
+
+/// Gravity a held soft drop is sped up from while gravity is off, that of the first level.
+const SOFT_DROP_BASE_GRAVITY: Duration = Duration::from_secs(1);
+
+/// Timing of the movement repeated while a key is held down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputSettings {
+    /// Delayed Auto Shift: how long left or right has to be held before the move starts repeating.
+    pub das: Duration,
+    /// Auto Repeat Rate: time between repeated moves once DAS ran out. Zero moves straight to the wall.
+    pub arr: Duration,
+    /// How many times faster than gravity a held soft drop moves down.
+    pub soft_drop_factor: u32,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        InputSettings {
+            das: Duration::from_millis(167),
+            arr: Duration::from_millis(33),
+            soft_drop_factor: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shift {
+    Left,
+    Right,
+    Down,
+}
+
+impl Shift {
+    fn idx(&self) -> usize {
+        match self {
+            Shift::Left => 0,
+            Shift::Right => 1,
+            Shift::Down => 2,
+        }
+    }
+}
+
+/// A single move to apply to the current object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShiftMove {
+    pub shift: Shift,
+    /// Repeated moves come from holding a key, they should stop silently at walls.
+    pub repeated: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct HeldKey {
+    /// Press not yet turned into a move. Kept after a release so quick taps are never lost.
+    pending: bool,
+    pressed_at: Option<Instant>,
+    next_repeat_at: Option<Instant>,
+}
+
+/// Tracks which movement keys are held down, and turns them into moves from the game thread.
+pub struct InputState {
+    settings: InputSettings,
+    keys: [HeldKey; 3],
+}
+
+impl InputState {
+    pub fn new(settings: InputSettings) -> Self {
+        InputState {
+            settings,
+            keys: [HeldKey::default(); 3],
+        }
+    }
+
+    pub fn press(&mut self, shift: Shift, now: Instant) {
+        let key = &mut self.keys[shift.idx()];
+        if key.pressed_at.is_some() {
+            // Already held, this is the terminal repeating the key
+            return;
+        }
+
+        key.pending = true;
+        key.pressed_at = Some(now);
+        key.next_repeat_at = None;
+    }
+
+    pub fn release(&mut self, shift: Shift) {
+        let key = &mut self.keys[shift.idx()];
+        key.pressed_at = None;
+        key.next_repeat_at = None;
+    }
+
+    /// Lets go of every key, for when release events can no longer be received.
+    pub fn release_all(&mut self) {
+        for key in self.keys.iter_mut() {
+            key.pressed_at = None;
+            key.next_repeat_at = None;
+        }
+    }
+
+    /// Collects the moves due at the given time. `gravity` is the current interval between rows,
+    /// `field_width` caps the moves an instant shift makes to reach the wall.
+    pub fn poll(&mut self, now: Instant, gravity: Duration, field_width: u16) -> Vec<ShiftMove> {
+        let mut moves = Vec::new();
+
+        for shift in [Shift::Left, Shift::Right, Shift::Down] {
+            let key = &mut self.keys[shift.idx()];
+            if key.pending {
+                key.pending = false;
+                moves.push(ShiftMove {
+                    shift,
+                    repeated: false,
+                });
+            }
+        }
+
+        // When both directions are held, the one pressed last wins
+        let left_pressed_at = self.keys[Shift::Left.idx()].pressed_at;
+        let right_pressed_at = self.keys[Shift::Right.idx()].pressed_at;
+        let horizontal = match (left_pressed_at, right_pressed_at) {
+            (Some(left), Some(right)) if right > left => Some(Shift::Right),
+            (Some(_), _) => Some(Shift::Left),
+            (None, Some(_)) => Some(Shift::Right),
+            (None, None) => None,
+        };
+
+        if let Some(shift) = horizontal {
+            let repeats = self.take_repeats(
+                shift,
+                now,
+                self.settings.das,
+                self.settings.arr,
+                field_width as u32,
+            );
+            moves.extend((0..repeats).map(|_| ShiftMove {
+                shift,
+                repeated: true,
+            }));
+        }
+
+        let gravity = if gravity == Duration::MAX {
+            SOFT_DROP_BASE_GRAVITY
+        } else {
+            gravity
+        };
+        let soft_drop_interval = (gravity / self.settings.soft_drop_factor.max(1))
+            .max(Duration::from_millis(1));
+        let repeats = self.take_repeats(
+            Shift::Down,
+            now,
+            soft_drop_interval,
+            soft_drop_interval,
+            u32::MAX,
+        );
+        moves.extend((0..repeats).map(|_| ShiftMove {
+            shift: Shift::Down,
+            repeated: true,
+        }));
+
+        moves
+    }
+
+    /// Counts the repeats of a held key that are due, `delay` after it was pressed and every `interval` after that.
+    fn take_repeats(
+        &mut self,
+        shift: Shift,
+        now: Instant,
+        delay: Duration,
+        interval: Duration,
+        max_repeats: u32,
+    ) -> u32 {
+        let key = &mut self.keys[shift.idx()];
+        let Some(pressed_at) = key.pressed_at else {
+            return 0;
+        };
+
+        let next_repeat_at = *key.next_repeat_at.get_or_insert(pressed_at + delay);
+        if now < next_repeat_at {
+            return 0;
+        }
+
+        if interval.is_zero() {
+            key.next_repeat_at = Some(now);
+            return max_repeats;
+        }
+
+        let repeats = ((now - next_repeat_at).as_micros() / interval.as_micros()) as u32 + 1;
+        key.next_repeat_at = Some(next_repeat_at + interval * repeats);
+        repeats.min(max_repeats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tetris_core::game::consts::field::FIELD_WIDTH;
+
+    const GRAVITY: Duration = Duration::from_millis(1000);
+
+    fn settings() -> InputSettings {
+        InputSettings {
+            das: Duration::from_millis(100),
+            arr: Duration::from_millis(20),
+            soft_drop_factor: 10,
+        }
+    }
+
+    fn count(moves: &[ShiftMove], shift: Shift, repeated: bool) -> usize {
+        moves
+            .iter()
+            .filter(|m| m.shift == shift && m.repeated == repeated)
+            .count()
+    }
+
+    #[test]
+    fn test_tap_moves_once() {
+        let mut input = InputState::new(settings());
+        let start = Instant::now();
+
+        input.press(Shift::Left, start);
+        input.release(Shift::Left);
+
+        let moves = input.poll(start + Duration::from_millis(500), GRAVITY, FIELD_WIDTH);
+        assert_eq!(
+            moves,
+            vec![ShiftMove {
+                shift: Shift::Left,
+                repeated: false
+            }]
+        );
+        assert!(input.poll(start + Duration::from_millis(600), GRAVITY, FIELD_WIDTH).is_empty());
+    }
+
+    #[test]
+    fn test_das_and_arr() {
+        let mut input = InputState::new(settings());
+        let start = Instant::now();
+        input.press(Shift::Right, start);
+
+        let moves = input.poll(start, GRAVITY, FIELD_WIDTH);
+        assert_eq!(count(&moves, Shift::Right, false), 1);
+
+        // Nothing repeats until DAS ran out
+        let moves = input.poll(start + Duration::from_millis(99), GRAVITY, FIELD_WIDTH);
+        assert!(moves.is_empty());
+
+        let moves = input.poll(start + Duration::from_millis(100), GRAVITY, FIELD_WIDTH);
+        assert_eq!(count(&moves, Shift::Right, true), 1);
+
+        // Then one move per ARR interval, catching up on missed polls
+        let moves = input.poll(start + Duration::from_millis(160), GRAVITY, FIELD_WIDTH);
+        assert_eq!(count(&moves, Shift::Right, true), 3);
+
+        input.release(Shift::Right);
+        assert!(input.poll(start + Duration::from_millis(500), GRAVITY, FIELD_WIDTH).is_empty());
+    }
+
+    #[test]
+    fn test_zero_arr_moves_to_wall() {
+        let mut input = InputState::new(InputSettings {
+            arr: Duration::ZERO,
+            ..settings()
+        });
+        let start = Instant::now();
+        input.press(Shift::Left, start);
+        input.poll(start, GRAVITY, FIELD_WIDTH);
+
+        let moves = input.poll(start + Duration::from_millis(100), GRAVITY, FIELD_WIDTH);
+        assert_eq!(count(&moves, Shift::Left, true), FIELD_WIDTH as usize);
+
+        // Wider fields take more moves to cross
+        let moves = input.poll(start + Duration::from_millis(120), GRAVITY, 40);
+        assert_eq!(count(&moves, Shift::Left, true), 40);
+    }
+
+    #[test]
+    fn test_last_pressed_direction_wins() {
+        let mut input = InputState::new(settings());
+        let start = Instant::now();
+        input.press(Shift::Left, start);
+        input.press(Shift::Right, start + Duration::from_millis(50));
+        input.poll(start + Duration::from_millis(50), GRAVITY, FIELD_WIDTH);
+
+        let moves = input.poll(start + Duration::from_millis(200), GRAVITY, FIELD_WIDTH);
+        assert_eq!(count(&moves, Shift::Left, true), 0);
+        assert!(count(&moves, Shift::Right, true) > 0);
+    }
+
+    #[test]
+    fn test_soft_drop_factor() {
+        let mut input = InputState::new(settings());
+        let start = Instant::now();
+        input.press(Shift::Down, start);
+        input.poll(start, GRAVITY, FIELD_WIDTH);
+
+        // Soft drop repeats at a tenth of the gravity interval, without any DAS
+        let moves = input.poll(start + Duration::from_millis(300), GRAVITY, FIELD_WIDTH);
+        assert_eq!(count(&moves, Shift::Down, true), 3);
+    }
+
+    #[test]
+    fn test_soft_drop_without_gravity() {
+        let mut input = InputState::new(settings());
+        let start = Instant::now();
+        input.press(Shift::Down, start);
+        input.poll(start, Duration::MAX, FIELD_WIDTH);
+
+        // Sped up from the gravity of the first level instead of never repeating
+        let moves = input.poll(start + Duration::from_millis(300), Duration::MAX, FIELD_WIDTH);
+        assert_eq!(count(&moves, Shift::Down, true), 3);
+    }
+
+    #[test]
+    fn test_release_all() {
+        let mut input = InputState::new(settings());
+        let start = Instant::now();
+        input.press(Shift::Left, start);
+        input.press(Shift::Down, start);
+        input.poll(start, GRAVITY, FIELD_WIDTH);
+
+        input.release_all();
+        assert!(input.poll(start + Duration::from_secs(1), GRAVITY, FIELD_WIDTH).is_empty());
+    }
+}
@@ -1,20 +1,103 @@
-use crate::game::Game;
-use crate::renderer::writers::size_screen;
-use crate::renderer::Renderer;
-use crossterm::event::{read, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyModifiers};
+use tetris_core::game::input::Input;
+use tetris_core::game::mode::ModeKind;
+use tetris_core::game::rendering::Renderer as _;
+use tetris_core::game::replay::Replay;
+use tetris_core::game::rules::Rules;
+use tetris_core::game::Game;
+use crate::audio::{AudioOutput, AudioPlayer, GameSounds, NullAudioPlayer};
+use crate::cli::{Command, PlayOptions, USAGE};
+use crate::config::CONFIG_FILE;
+use crate::headless::{print_scores, run_bench, run_bot};
+use crate::input::bindings::Action;
+use crate::input::{InputState, Shift, ShiftMove};
+use crate::playback::run_replay;
+use crate::scores::board::ScoreBoard;
+use crate::scores::{HighScores, SCORES_FILE};
+use crate::storage::write_atomically;
+use crate::renderer::layout::Layout;
+use crate::renderer::writers::{
+    clear_screen, size_screen, write_border, write_continue_prompt, write_mode_select,
+};
+use crate::renderer::{RenderSettings, Renderer};
+use crossterm::event::{
+    read, DisableFocusChange, EnableFocusChange, Event, KeyCode, KeyEventKind,
+    KeyModifiers, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
 use crossterm::terminal::{ClearType, SetSize};
 use crossterm::{cursor, execute, terminal};
-use rodio::OutputStream;
 use std::io::{self, stdout};
-use std::sync::{Arc, Mutex};
+use std::path::Path;
+use std::{env, fs, process};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 mod audio;
-mod game;
+mod cli;
+mod config;
+mod headless;
+mod input;
+mod playback;
 mod renderer;
+mod scores;
+mod storage;
+
+/// File the game in progress is saved to when quitting, in the data directory.
+const SAVE_FILE: &str = "save.bin";
+
+/// Pause between two game loop iterations, short enough for the auto repeat rate to stay accurate.
+const GAME_LOOP_INTERVAL: Duration = Duration::from_millis(4);
 
 fn main() -> io::Result<()> {
+    let args = env::args().skip(1).collect();
+    let command = match cli::parse(args, &storage::config_dir().join(CONFIG_FILE)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("Run with --help to see the options");
+            process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Help => println!("{}", USAGE),
+        Command::Version => println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        Command::Scores(mode) => print_scores(mode.map(|mode| mode.name())),
+        Command::Bot(options) => process::exit(run_bot(&options)),
+        Command::Bench(options) => run_bench(&options),
+        Command::Replay(options) if options.verify => process::exit(verify_replay(&options.path)),
+        Command::Replay(options) => {
+            let replay = match load_replay(&options.path) {
+                Ok(replay) => replay,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    process::exit(1);
+                }
+            };
+            in_terminal(|_| run_replay(replay, options.render))?;
+        }
+        Command::Play(options) => {
+            let (recording, errors) = in_terminal(|tracks| run_game(&options, tracks))?;
+            for error in &errors {
+                eprintln!("{}", error);
+            }
+
+            if let (Some(path), Some(recording)) = (&options.record, recording)
+                && let Err(error) = fs::write(path, recording.to_bytes())
+            {
+                eprintln!("Failed to save the replay to {}: {}", path.display(), error);
+                process::exit(1);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Switches the terminal to raw mode for the duration of `run`, restoring it afterwards.
+/// `run` is told whether the terminal reports released keys.
+fn in_terminal<T>(run: impl FnOnce(bool) -> T) -> io::Result<T> {
     let screen_size = terminal::size()?;
     let raw_mode_enabled = terminal::is_raw_mode_enabled()?;
     if !raw_mode_enabled {
@@ -22,8 +105,21 @@ fn main() -> io::Result<()> {
     }
     execute!(stdout(), EnableFocusChange)?;
 
-    run_game();
+    // Key release events are needed to know how long a key is held down.
+    // Windows always reports them, other terminals have to support the keyboard enhancement protocol.
+    let keyboard_enhanced = terminal::supports_keyboard_enhancement().unwrap_or(false);
+    if keyboard_enhanced {
+        execute!(
+            stdout(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+        )?;
+    }
 
+    let result = run(keyboard_enhanced || cfg!(windows));
+
+    if keyboard_enhanced {
+        execute!(stdout(), PopKeyboardEnhancementFlags)?;
+    }
     if !raw_mode_enabled {
         terminal::disable_raw_mode()?;
     }
@@ -35,80 +131,336 @@ fn main() -> io::Result<()> {
         SetSize(screen_size.0, screen_size.1)
     )?;
 
-    Ok(())
+    Ok(result)
+}
+
+fn load_replay(path: &Path) -> Result<Replay, String> {
+    let bytes = fs::read(path)
+        .map_err(|error| format!("Failed to read the replay {}: {}", path.display(), error))?;
+    Replay::from_bytes(&bytes)
+        .map_err(|error| format!("Failed to load the replay {}: {}", path.display(), error))
 }
 
-fn run_game() {
-    let renderer = Box::new(Renderer::new());
-
-    let (_output_stream, stream_handle) = match OutputStream::try_default() {
-        Ok(tuple) => tuple,
-        Err(_) => {
-            // If audio initialization fails, create dummy stream/handle to avoid panics
-            // This is a workaround if OutputStream::try_default() fails
-            // In a real scenario, you might want to handle this more gracefully,
-            // e.g., by returning a Result from run_game or using a NoOpAudioPlayer.
-            // For now, we'll just panic if it's truly unrecoverable, as try_default should work.
-            panic!("Failed to initialize audio output stream. Is an audio device available?");
+/// Plays the replay at the given path without showing it, returning the exit code:
+/// 0 if it ends with the recorded result, 1 if it does not or cannot be read.
+fn verify_replay(path: &Path) -> i32 {
+    let result = load_replay(path).and_then(|replay| replay.verify());
+    match result {
+        Ok(result) => {
+            println!(
+                "Replay verified: score {}, {} lines, level {} after {} frames",
+                result.score, result.lines, result.level, result.frames
+            );
+            0
+        }
+        Err(message) => {
+            eprintln!("{}", message);
+            1
+        }
+    }
+}
+
+fn apply_shift(game: &mut Game, ShiftMove { shift, repeated }: ShiftMove) {
+    let (x, y) = match shift {
+        Shift::Left => (-1, 0),
+        Shift::Right => (1, 0),
+        Shift::Down => (0, 1),
+    };
+
+    // Held keys stop silently at walls instead of bumping into them over and over
+    if repeated && !game.can_move_current_object(x, y) {
+        return;
+    }
+
+    game.apply(match shift {
+        Shift::Left => Input::MoveLeft,
+        Shift::Right => Input::MoveRight,
+        Shift::Down => Input::SoftDrop,
+    });
+}
+
+/// `tracks_key_releases` tells whether the terminal reports released keys.
+/// Without them, every key press is handled as a short tap.
+/// Returns a recording of the last game played, unless nothing was played at all,
+/// and the problems met loading or saving games and high scores.
+fn run_game(options: &PlayOptions, tracks_key_releases: bool) -> (Option<Replay>, Vec<String>) {
+    let data_dir = storage::data_dir();
+    let save_path = data_dir.join(SAVE_FILE);
+    let mut errors = Vec::new();
+
+    let mut settings = options.render.clone();
+
+    // Kept alive until the game ends, the sounds stop playing once it is dropped.
+    let (_audio_output, audio_player): (Option<AudioOutput>, Box<dyn AudioPlayer>) =
+        match options.audio.then(|| audio::open(options.volumes)) {
+            Some(Ok((output, player))) => (Some(output), player),
+            Some(Err(error)) => {
+                errors.push(format!("Played without audio: {}", error));
+                settings.notice = Some(" NO AUDIO ".to_string());
+                (None, Box::new(NullAudioPlayer))
+            }
+            None => (None, Box::new(NullAudioPlayer)),
+        };
+
+    let mut game = match offer_saved_game(&save_path, &settings, &mut errors) {
+        Some(game) => game,
+        None => {
+            let Some(mode) = options.mode.or_else(|| select_mode(&options.rules)) else {
+                return (None, errors);
+            };
+            let rules = Rules {
+                mode,
+                ..options.rules.clone()
+            };
+            Game::new(Box::new(Renderer::new(settings.clone())), rules, options.seed)
         }
     };
-    let arc_stream_handle = Arc::new(stream_handle);
+    game.subscribe(Box::new(GameSounds::new(audio_player)));
+
+    let (events_sender, events) = mpsc::channel();
+    game.subscribe(Box::new(events_sender));
+    let scores_path = data_dir.join(SCORES_FILE);
+    let scores = HighScores::load(scores_path);
+    let board = ScoreBoard::new(scores, events, settings.clone());
+    let bindings = &options.render.bindings;
 
-    let audio_player = Box::new(audio::AudioPlayer::new(arc_stream_handle.clone()));
-    let game_mx = Arc::new(Mutex::new(Game::new(renderer, audio_player)));
-    let game_mx_clone = Arc::clone(&game_mx);
+    let session_mx = Arc::new(Mutex::new(Session { game, board }));
+    let session_mx_clone = Arc::clone(&session_mx);
+
+    let input_mx = Arc::new(Mutex::new(InputState::new(options.input)));
+    let input_mx_clone = Arc::clone(&input_mx);
+    let mut recording = None;
 
     let game_thread = thread::spawn(move || {
         loop {
             {
-                let mut game = game_mx_clone.lock().unwrap();
+                let mut session = session_mx_clone.lock().unwrap();
+                let Session { game, board } = &mut *session;
                 if game.should_exit() {
                     break;
                 }
 
+                let gravity = game.gravity_interval();
+                let moves = input_mx_clone
+                    .lock()
+                    .unwrap()
+                    .poll(Instant::now(), gravity, game.field.width);
+                if !game.is_paused() && !game.is_game_over() {
+                    for shift_move in moves {
+                        apply_shift(game, shift_move);
+                    }
+                }
+
                 game.tick();
+                board.poll_events(game);
             }
 
-            thread::sleep(Duration::from_millis(32));
+            thread::sleep(GAME_LOOP_INTERVAL);
         }
     });
 
     loop {
         let e = read().expect("Error reading inputs");
-        let mut game = game_mx.lock().unwrap();
+
+        {
+            // Screens of the score board take all keys while they are shown
+            let mut session = session_mx.lock().unwrap();
+            let Session { game, board } = &mut *session;
+            if board.is_shown() {
+                if let Event::Key(event) = e
+                    && event.is_press()
+                {
+                    let ctrl_c = event.modifiers == KeyModifiers::CONTROL;
+                    if ctrl_c && event.code == KeyCode::Char('c') {
+                        board.finish(game);
+                        game.exit();
+                        break;
+                    }
+                    board.handle_key(event.code, game);
+                }
+                continue;
+            }
+        }
+
+        if let Event::Key(event) = e
+            && let Some(shift) = bindings.action_for(event.code).and_then(|action| action.shift())
+        {
+            // Movement is applied by the game thread, which repeats it while the key is held
+            let mut input = input_mx.lock().unwrap();
+            match event.kind {
+                KeyEventKind::Press => {
+                    input.press(shift, Instant::now());
+                    if !tracks_key_releases {
+                        input.release(shift);
+                    }
+                }
+                KeyEventKind::Release => input.release(shift),
+                KeyEventKind::Repeat => {}
+            }
+            continue;
+        }
+
+        let mut session = session_mx.lock().unwrap();
+        let Session { game, board } = &mut *session;
 
         match e {
             Event::FocusLost => {
+                // Keys released while unfocused are never reported
+                input_mx.lock().unwrap().release_all();
                 game.set_paused(true);
             }
             Event::Key(event) => {
-                if event.is_release() {
-                    continue; // Skip key release events to unwanted prevent double actions
+                if !event.is_press() {
+                    continue; // Skip key release and repeat events to prevent unwanted double actions
                 }
-                
+
                 if event.code == KeyCode::Char('c') && event.modifiers == KeyModifiers::CONTROL {
                     game.exit();
                     break;
-                } else if event.code == KeyCode::Char(' ') {
-                    let paused = game.is_paused();
-                    game.set_paused(!paused);
-                } else if event.code == KeyCode::Backspace {
-                    game.reset();
-                } else if !game.is_paused() && !game.is_game_over() {
-                    match event.code {
-                        KeyCode::Char('r') => game.rotate_current_object(),
-                        KeyCode::Char('a') => game.move_current_object_left(),
-                        KeyCode::Char('d') => game.move_current_object_right(),
-                        KeyCode::Char('s') => game.move_current_object_down(),
-                        KeyCode::Char('w') => game.drop_current_object_down(),
-                        _ => continue,
+                }
+
+                match bindings.action_for(event.code) {
+                    Some(Action::Pause) => {
+                        let paused = game.is_paused();
+                        game.set_paused(!paused);
+                    }
+                    Some(Action::Restart) => {
+                        let replay = game.replay();
+                        if !replay.inputs.is_empty() {
+                            recording = Some(replay);
+                        }
+                        game.reset();
+                    }
+                    Some(Action::HighScores) if game.is_paused() => board.show_table(game),
+                    Some(action) if !game.is_paused() && !game.is_game_over() => {
+                        if let Some(input) = action.input() {
+                            game.apply(input);
+                        }
                     }
+                    _ => {}
                 }
             }
-            Event::Resize(_, _) => size_screen(&mut stdout()).expect("resize failed"),
+            Event::Resize(_, _) => {
+                let layout = Layout::new(&game.rules);
+                size_screen(&mut stdout(), &layout).expect("resize failed");
+            }
             _ => continue,
         }
     }
 
     game_thread.join().unwrap();
+
+    let mut session = session_mx.lock().unwrap();
+    errors.append(&mut session.board.take_errors());
+
+    // Games quit halfway are saved to be continued next time
+    if !session.game.is_game_over() && session.game.get_frame() > 0 {
+        let saved = write_atomically(&save_path, &session.game.save_state());
+        if let Err(error) = saved {
+            errors.push(format!(
+                "Failed to save the game to {}: {}",
+                save_path.display(),
+                error
+            ));
+        }
+    }
+
+    let replay = session.game.replay();
+    let recording = if replay.inputs.is_empty() {
+        recording
+    } else {
+        Some(replay)
+    };
+    (recording, errors)
+}
+
+/// Offers to continue the game saved when the last session was quit, returning it if the
+/// player wants to. Saved games are only offered once, and ones that fail to load are reported.
+fn offer_saved_game(
+    path: &Path,
+    settings: &RenderSettings,
+    errors: &mut Vec<String>,
+) -> Option<Game> {
+    let bytes = fs::read(path).ok()?;
+    let _ = fs::remove_file(path);
+
+    let renderer = Box::new(Renderer::new(settings.clone()));
+    let game = match Game::load_state(&bytes, renderer) {
+        Ok(game) => game,
+        Err(error) => {
+            errors.push(format!(
+                "Discarded the saved game {}: {}",
+                path.display(),
+                error
+            ));
+            return None;
+        }
+    };
+
+    let layout = Layout::new(&game.rules);
+    write_continue_prompt(&mut stdout(), &layout, &game).expect("Failed to write prompt");
+    let resume = loop {
+        let Event::Key(event) = read().expect("Error reading inputs") else {
+            continue;
+        };
+        match event.code {
+            _ if !event.is_press() => {}
+            KeyCode::Char('y') | KeyCode::Enter => break true,
+            KeyCode::Char('n') | KeyCode::Esc => break false,
+            _ => {}
+        }
+    };
+    if !resume {
+        return None;
+    }
+
+    // Draw the game again without the prompt
+    let renderer = Renderer::new(settings.clone());
+    renderer.prerender(&game);
+    renderer.render(&game);
+    Some(game)
+}
+
+/// Asks for the mode to play, returning None if the player quits instead.
+fn select_mode(rules: &Rules) -> Option<ModeKind> {
+    let layout = Layout::new(rules);
+    let mut selected = ModeKind::ALL
+        .iter()
+        .position(|&mode| mode == rules.mode)
+        .unwrap_or(0);
+
+    let mut out = stdout();
+    clear_screen(&mut out, &layout).expect("Failed to clear the screen");
+    write_border(&mut out, &layout).expect("Failed to write border");
+    loop {
+        write_mode_select(&mut out, &layout, selected).expect("Failed to write mode select");
+
+        let Event::Key(event) = read().expect("Error reading inputs") else {
+            continue;
+        };
+        if !event.is_press() {
+            continue;
+        }
+        match event.code {
+            KeyCode::Char('c') if event.modifiers == KeyModifiers::CONTROL => return None,
+            KeyCode::Up | KeyCode::Char('w') => {
+                selected = (selected + ModeKind::ALL.len() - 1) % ModeKind::ALL.len();
+            }
+            KeyCode::Down | KeyCode::Char('s') => selected = (selected + 1) % ModeKind::ALL.len(),
+            KeyCode::Char(digit @ '1'..='9') => {
+                let idx = digit as usize - '1' as usize;
+                if idx < ModeKind::ALL.len() {
+                    selected = idx;
+                }
+            }
+            KeyCode::Enter => return Some(ModeKind::ALL[selected]),
+            _ => {}
+        }
+    }
+}
+
+/// State shared by the game loop and the key handling.
+struct Session {
+    game: Game,
+    board: ScoreBoard,
 }
@@ -0,0 +1,214 @@
+use tetris_core::game::replay::{Replay, ReplayPlayer};
+use tetris_core::game::{Game, FRAME_DURATION};
+use crate::renderer::layout::Layout;
+use crate::renderer::writers::write_status;
+use crate::renderer::{RenderSettings, Renderer};
+use crossterm::event::{read, Event, KeyCode, KeyModifiers};
+use std::io::stdout;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Speeds a replay can be played at, as multiples of real time.
+const SPEEDS: [f64; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+const NORMAL_SPEED: usize = 2;
+
+/// Pause between two playback loop iterations.
+const PLAYBACK_LOOP_INTERVAL: Duration = Duration::from_millis(4);
+
+/// Controls of a replay being watched: play/pause, speed and single frame steps.
+pub struct Playback {
+    pub playing: bool,
+    speed: usize,
+    /// Frames asked for by stepping while paused
+    pending_steps: u32,
+    /// Time played back that did not add up to a whole frame yet
+    frame_remainder: Duration,
+}
+
+impl Playback {
+    pub fn new() -> Self {
+        Playback {
+            playing: true,
+            speed: NORMAL_SPEED,
+            pending_steps: 0,
+            frame_remainder: Duration::ZERO,
+        }
+    }
+
+    pub fn get_speed(&self) -> f64 {
+        SPEEDS[self.speed]
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    pub fn toggle(&mut self) {
+        self.playing = !self.playing;
+        self.frame_remainder = Duration::ZERO;
+    }
+
+    /// Pauses playback and moves on by a single frame.
+    pub fn step_frame(&mut self) {
+        self.playing = false;
+        self.pending_steps += 1;
+    }
+
+    /// Frames to play for `dt` of real time passing.
+    pub fn frames_for(&mut self, dt: Duration) -> u32 {
+        if !self.playing {
+            return std::mem::take(&mut self.pending_steps);
+        }
+
+        self.frame_remainder += dt.mul_f64(self.get_speed());
+        let frames = self.frame_remainder.as_nanos() / FRAME_DURATION.as_nanos();
+        self.frame_remainder -= FRAME_DURATION * frames as u32;
+        frames as u32
+    }
+
+    fn status(&self, finished: bool) -> String {
+        let state = if finished {
+            "END"
+        } else if self.playing {
+            "PLAY"
+        } else {
+            "PAUSE"
+        };
+        format!(" REPLAY {} {}x ", state, self.get_speed())
+    }
+}
+
+/// Replay being watched, shared between the playback thread and the key handling.
+struct Session {
+    player: ReplayPlayer,
+    game: Game,
+    playback: Playback,
+    should_exit: bool,
+}
+
+/// Plays a replay through the normal renderer until the viewer quits.
+/// Space plays or pauses, + and - change the speed, . steps a single frame, q quits.
+pub fn run_replay(replay: Replay, settings: RenderSettings) {
+    let layout = Layout::new(&replay.rules);
+    let mut player = ReplayPlayer::new(replay);
+    let game = player.start(Box::new(Renderer::new(settings)));
+
+    let session_mx = Arc::new(Mutex::new(Session {
+        player,
+        game,
+        playback: Playback::new(),
+        should_exit: false,
+    }));
+    let session_mx_clone = Arc::clone(&session_mx);
+
+    let playback_thread = thread::spawn(move || {
+        let mut last_loop_at = Instant::now();
+        let mut last_status = String::new();
+        loop {
+            {
+                let mut session = session_mx_clone.lock().unwrap();
+                let Session {
+                    player,
+                    game,
+                    playback,
+                    should_exit,
+                } = &mut *session;
+                if *should_exit {
+                    break;
+                }
+
+                let now = Instant::now();
+                for _ in 0..playback.frames_for(now - last_loop_at) {
+                    if player.is_finished(game) {
+                        break;
+                    }
+                    player.step(game);
+                }
+                last_loop_at = now;
+
+                let status = playback.status(player.is_finished(game));
+                if status != last_status {
+                    let _ = write_status(&mut stdout(), &layout, &status);
+                    last_status = status;
+                }
+            }
+
+            thread::sleep(PLAYBACK_LOOP_INTERVAL);
+        }
+    });
+
+    loop {
+        let Event::Key(event) = read().expect("Error reading inputs") else {
+            continue;
+        };
+        if !event.is_press() {
+            continue;
+        }
+
+        let playback = &mut session_mx.lock().unwrap().playback;
+        match event.code {
+            KeyCode::Char('c') if event.modifiers == KeyModifiers::CONTROL => break,
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char(' ') => playback.toggle(),
+            KeyCode::Char('+') | KeyCode::Char('=') => playback.faster(),
+            KeyCode::Char('-') => playback.slower(),
+            KeyCode::Char('.') => playback.step_frame(),
+            _ => {}
+        }
+    }
+
+    session_mx.lock().unwrap().should_exit = true;
+    playback_thread.join().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speed_stays_within_range() {
+        let mut playback = Playback::new();
+        assert_eq!(playback.get_speed(), 1.0);
+
+        for _ in 0..10 {
+            playback.faster();
+        }
+        assert_eq!(playback.get_speed(), 8.0);
+
+        for _ in 0..10 {
+            playback.slower();
+        }
+        assert_eq!(playback.get_speed(), 0.25);
+    }
+
+    #[test]
+    fn test_frames_follow_speed() {
+        let mut playback = Playback::new();
+        assert_eq!(playback.frames_for(FRAME_DURATION * 10), 10);
+
+        playback.faster();
+        assert_eq!(playback.frames_for(FRAME_DURATION * 10), 20);
+
+        playback.slower();
+        playback.slower();
+        playback.slower();
+        assert_eq!(playback.frames_for(FRAME_DURATION * 10), 2);
+        assert_eq!(playback.frames_for(FRAME_DURATION * 2), 1);
+    }
+
+    #[test]
+    fn test_stepping_pauses_and_plays_single_frames() {
+        let mut playback = Playback::new();
+        playback.step_frame();
+        playback.step_frame();
+        assert!(!playback.playing);
+
+        assert_eq!(playback.frames_for(Duration::from_secs(1)), 2);
+        assert_eq!(playback.frames_for(Duration::from_secs(1)), 0);
+    }
+}
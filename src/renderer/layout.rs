@@ -0,0 +1,200 @@
+use super::consts::{H_OFFSET, MIN_PREVIEW_CELLS, V_OFFSET};
+use tetris_core::game::consts::field::{FIELD_HEIGHT, FIELD_WIDTH};
+use tetris_core::game::rules::{Rules, MAX_PREVIEW_SIZE};
+
+/// Stats the sidebar has room for between the next and the held piece.
+const STAT_SLOTS: u16 = 3;
+
+/// Arrangement of the screen, which grows with the preview queue, the field and the pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// Whether the sidebar has a second column, holding the preview queue after the first piece.
+    pub queue_column: bool,
+    /// Pieces shown in the preview queue.
+    pub preview_size: usize,
+    /// Cells the piece previews span, enough for the largest piece of the set.
+    pub piece_size: u16,
+    pub field_width: u16,
+    /// Visible rows of the field.
+    pub field_height: u16,
+    /// Hidden rows above the visible ones, which are not drawn.
+    pub field_buffer: u16,
+}
+
+impl Layout {
+    pub fn new(rules: &Rules) -> Self {
+        Layout {
+            queue_column: rules.preview_size > 1,
+            preview_size: rules.preview_size.clamp(1, MAX_PREVIEW_SIZE),
+            piece_size: rules.pieces.get_max_size().max(MIN_PREVIEW_CELLS),
+            field_width: rules.field_width,
+            field_height: rules.field_height,
+            field_buffer: rules.field_buffer,
+        }
+    }
+
+    /// Inner width of the well the field sits in. Fields narrower than the standard one are
+    /// centered in a well of the standard width, so the sidebar and the modals keep their room.
+    pub fn well_width(&self) -> u16 {
+        self.field_width.max(FIELD_WIDTH) * 2
+    }
+
+    /// Inner height of the well. Fields lower than the standard one sit on its floor,
+    /// as do fields lower than the sidebar needs for large pieces.
+    pub fn well_height(&self) -> u16 {
+        let queue_height = (self.preview_size as u16 - 1) * self.piece_size;
+        let sidebar_height = self.hold_y() + self.piece_size - V_OFFSET;
+        self.field_height.max(FIELD_HEIGHT).max(queue_height).max(sidebar_height)
+    }
+
+    /// Width of a sidebar column, which fits a piece preview.
+    pub fn column_width(&self) -> u16 {
+        self.piece_size * 2
+    }
+
+    pub fn sidebar_width(&self) -> u16 {
+        if self.queue_column {
+            self.column_width() * 2 + 1
+        } else {
+            self.column_width()
+        }
+    }
+
+    pub fn container_width(&self) -> u16 {
+        self.well_width() + 2 + self.sidebar_width() + 1
+    }
+
+    pub fn container_height(&self) -> u16 {
+        self.well_height() + 2
+    }
+
+    pub fn screen_width(&self) -> u16 {
+        self.container_width() + H_OFFSET * 2
+    }
+
+    pub fn screen_height(&self) -> u16 {
+        self.container_height() + V_OFFSET * 2
+    }
+
+    /// Left edge of the sidebar, right of the well.
+    pub fn sidebar_x(&self) -> u16 {
+        H_OFFSET + self.well_width() + 2
+    }
+
+    /// Screen position of the top left visible cell of the field.
+    pub fn field_origin(&self) -> (u16, u16) {
+        (
+            H_OFFSET + 1 + self.well_width() / 2 - self.field_width,
+            V_OFFSET + 1 + self.well_height() - self.field_height,
+        )
+    }
+
+    /// Screen position of the given cell of the field, counting the buffer rows,
+    /// or None for the cells in the buffer, which are hidden.
+    pub fn cell_position(&self, x: u16, y: u16) -> Option<(u16, u16)> {
+        let (origin_x, origin_y) = self.field_origin();
+        let y = y.checked_sub(self.field_buffer)?;
+        Some((origin_x + x * 2, origin_y + y))
+    }
+
+    /// Left edge of the column holding the preview queue, right of the stats column.
+    pub fn queue_x(&self) -> u16 {
+        self.sidebar_x() + self.column_width() + 1
+    }
+
+    /// Top edge of the slot showing the given piece of the queue, the first one being shown as next.
+    pub fn queue_slot_y(&self, idx: usize) -> u16 {
+        V_OFFSET + 1 + (idx as u16 - 1) * self.piece_size
+    }
+
+    /// Line of the label of the given stat, under the next piece.
+    pub fn stat_y(&self, slot: u16) -> u16 {
+        V_OFFSET + 3 + self.piece_size + slot * 3
+    }
+
+    /// Line of the hold label, under the stats.
+    pub fn hold_y(&self) -> u16 {
+        self.stat_y(STAT_SLOTS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tetris_core::game::piece_set::PieceSet;
+
+    fn layout_of(preview_size: usize, (field_width, field_height): (u16, u16)) -> Layout {
+        Layout::new(&Rules {
+            preview_size,
+            field_width,
+            field_height,
+            ..Rules::default()
+        })
+    }
+
+    #[test]
+    fn test_single_piece_keeps_narrow_sidebar() {
+        let layout = layout_of(1, (FIELD_WIDTH, FIELD_HEIGHT));
+        assert!(!layout.queue_column);
+        assert_eq!(layout.sidebar_width(), MIN_PREVIEW_CELLS * 2);
+    }
+
+    #[test]
+    fn test_queue_column_fits_field_height() {
+        let layout = layout_of(MAX_PREVIEW_SIZE, (FIELD_WIDTH, FIELD_HEIGHT));
+        assert!(layout.queue_column);
+        assert!(layout.screen_width() > layout_of(1, (FIELD_WIDTH, FIELD_HEIGHT)).screen_width());
+
+        let last_slot_bottom = layout.queue_slot_y(MAX_PREVIEW_SIZE - 1) + layout.piece_size - 1;
+        assert!(last_slot_bottom <= V_OFFSET + FIELD_HEIGHT);
+        assert_eq!(layout.well_height(), FIELD_HEIGHT);
+        assert_eq!((layout.stat_y(0), layout.hold_y()), (V_OFFSET + 7, V_OFFSET + 16));
+    }
+
+    #[test]
+    fn test_sidebar_grows_with_the_pieces() {
+        let rules = Rules {
+            pieces: PieceSet::builtin("pentomino").unwrap(),
+            preview_size: MAX_PREVIEW_SIZE,
+            ..Rules::default()
+        };
+        let layout = Layout::new(&rules);
+        assert_eq!(layout.piece_size, 5);
+        assert_eq!(layout.column_width(), 10);
+
+        let last_slot_bottom = layout.queue_slot_y(MAX_PREVIEW_SIZE - 1) + layout.piece_size - 1;
+        assert!(last_slot_bottom <= V_OFFSET + layout.well_height());
+        assert!(layout.hold_y() + layout.piece_size <= V_OFFSET + layout.well_height());
+        // The field still sits on the floor of the taller well
+        assert_eq!(layout.field_origin().1 + FIELD_HEIGHT, V_OFFSET + 1 + layout.well_height());
+
+        let trominoes = Rules {
+            pieces: PieceSet::builtin("tromino").unwrap(),
+            ..Rules::default()
+        };
+        assert_eq!(Layout::new(&trominoes), Layout::new(&Rules::default()));
+    }
+
+    #[test]
+    fn test_screen_grows_with_the_field() {
+        let standard = layout_of(1, (FIELD_WIDTH, FIELD_HEIGHT));
+        let big = layout_of(1, (20, 40));
+        assert_eq!(big.screen_width(), standard.screen_width() + 20);
+        assert_eq!(big.screen_height(), standard.screen_height() + 20);
+        assert_eq!(big.field_origin(), (H_OFFSET + 1, V_OFFSET + 1));
+    }
+
+    #[test]
+    fn test_small_field_sits_centered_on_the_floor() {
+        let standard = layout_of(1, (FIELD_WIDTH, FIELD_HEIGHT));
+        let small = layout_of(1, (4, 10));
+        assert_eq!(small.screen_width(), standard.screen_width());
+        assert_eq!(small.screen_height(), standard.screen_height());
+
+        let (x, y) = small.field_origin();
+        assert_eq!(x - H_OFFSET - 1, small.well_width() - (x - H_OFFSET - 1) - 8);
+        assert_eq!(y + 10, V_OFFSET + 1 + FIELD_HEIGHT);
+        assert_eq!(small.cell_position(0, small.field_buffer), Some((x, y)));
+        assert_eq!(small.cell_position(3, 0), None);
+    }
+}
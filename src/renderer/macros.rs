@@ -8,6 +8,11 @@ macro_rules! filled_block {
     () => { "▓▓" };
 }
 
+#[macro_export]
+macro_rules! ghost_block {
+    () => { "░░" };
+}
+
 #[macro_export]
 macro_rules! n_write {
     ($out:expr, $c:expr, $count:expr) => {
@@ -1,28 +1,65 @@
-use crate::game::{rendering, Game};
-use crate::renderer::writers::{clear_screen, write_border, write_game};
+use tetris_core::game::{rendering, Game};
+use crate::input::bindings::KeyBindings;
+use crate::renderer::layout::Layout;
+use crate::renderer::theme::Theme;
+use crate::renderer::writers::{clear_screen, write_border, write_game, write_status};
 use std::io::stdout;
 
 pub mod consts;
+pub mod layout;
 pub mod macros;
+pub mod theme;
 pub mod writers;
 
-pub struct Renderer {}
+/// Visual preferences that do not affect the game itself.
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    /// Whether to draw the ghost piece showing where the current object will land.
+    pub show_ghost: bool,
+    /// Whether to draw the upcoming pieces. The queue keeps its place on the screen when hidden.
+    pub show_preview: bool,
+    pub theme: Theme,
+    /// Keys listed on the pause screen.
+    pub bindings: KeyBindings,
+    /// Short notice shown in the top border, such as the game playing without sound.
+    pub notice: Option<String>,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            show_ghost: true,
+            show_preview: true,
+            theme: Theme::default(),
+            bindings: KeyBindings::default(),
+            notice: None,
+        }
+    }
+}
+
+pub struct Renderer {
+    settings: RenderSettings,
+}
 
 impl Renderer {
-    pub fn new() -> Self {
-        Renderer {}
+    pub fn new(settings: RenderSettings) -> Self {
+        Renderer { settings }
     }
 }
 
 impl rendering::Renderer for Renderer {
     fn render(&self, game: &Game) {
         let out = &mut stdout();
-        write_game(out, game).expect("Failed to write game state");
+        write_game(out, game, &self.settings).expect("Failed to write game state");
     }
 
-    fn prerender(&self) {
+    fn prerender(&self, game: &Game) {
         let out = &mut stdout();
-        clear_screen(out).expect("Failed to clear screen");
-        write_border(out).expect("Failed to write border");
+        let layout = Layout::new(&game.rules);
+        clear_screen(out, &layout).expect("Failed to clear screen");
+        write_border(out, &layout).expect("Failed to write border");
+        if let Some(notice) = &self.settings.notice {
+            write_status(out, &layout, notice).expect("Failed to write notice");
+        }
     }
 }
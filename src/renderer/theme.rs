@@ -0,0 +1,124 @@
+use tetris_core::game::piece_set::{Piece, PieceColor};
+use crossterm::style::Color;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// How a theme shows the colors the piece set gives its pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Palette {
+    /// As the set gives them.
+    Set,
+    /// As the set gives them, except for the listed ones.
+    Swapped(&'static [(PieceColor, Color)]),
+    /// All pieces alike.
+    Single(Color),
+}
+
+/// Colors the game is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    name: &'static str,
+    pieces: Palette,
+    /// Blocks of the stack.
+    pub filled: Color,
+    /// Free cells of the field and of the piece previews.
+    pub empty: Color,
+    pub ghost: Color,
+    /// Held piece while it cannot be swapped back in.
+    pub hold_locked: Color,
+    pub line_clear: Color,
+}
+
+impl Theme {
+    /// The colors the game always had, which differ from the guideline for the L, O and Z.
+    pub const CLASSIC: Theme = Theme {
+        name: "classic",
+        pieces: Palette::Swapped(&[
+            (PieceColor::Orange, Color::Yellow),
+            (PieceColor::Yellow, Color::Red),
+            (PieceColor::Red, Color::White),
+        ]),
+        filled: Color::White,
+        empty: Color::Grey,
+        ghost: Color::DarkGrey,
+        hold_locked: Color::DarkGrey,
+        line_clear: Color::Yellow,
+    };
+
+    /// The standard colors of modern Tetris games, which the standard piece set gives.
+    pub const GUIDELINE: Theme = Theme {
+        name: "guideline",
+        pieces: Palette::Set,
+        ..Theme::CLASSIC
+    };
+
+    /// Shades of grey only, for terminals with few colors or players who prefer less of them.
+    pub const MONOCHROME: Theme = Theme {
+        name: "monochrome",
+        pieces: Palette::Single(Color::White),
+        filled: Color::Grey,
+        empty: Color::DarkGrey,
+        ghost: Color::DarkGrey,
+        hold_locked: Color::DarkGrey,
+        line_clear: Color::White,
+    };
+
+    pub const ALL: [Theme; 3] = [Theme::CLASSIC, Theme::GUIDELINE, Theme::MONOCHROME];
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn get_piece_color(&self, piece: &Piece) -> Color {
+        let color = piece.color();
+        match self.pieces {
+            Palette::Set => terminal_color(color),
+            Palette::Swapped(swaps) => swaps
+                .iter()
+                .find(|(from, _)| *from == color)
+                .map_or_else(|| terminal_color(color), |&(_, to)| to),
+            Palette::Single(single) => single,
+        }
+    }
+}
+
+fn terminal_color(color: PieceColor) -> Color {
+    match color {
+        PieceColor::Red => Color::Red,
+        PieceColor::Green => Color::Green,
+        PieceColor::Yellow => Color::Yellow,
+        PieceColor::Blue => Color::Blue,
+        PieceColor::Magenta => Color::Magenta,
+        PieceColor::Cyan => Color::Cyan,
+        PieceColor::White => Color::White,
+        PieceColor::Orange => Color::Rgb { r: 255, g: 160, b: 0 },
+        PieceColor::Grey => Color::Grey,
+        PieceColor::Rgb(r, g, b) => Color::Rgb { r, g, b },
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::CLASSIC
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Theme::ALL
+            .into_iter()
+            .find(|theme| theme.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Theme::ALL.iter().map(|theme| theme.name()).collect();
+                format!("Unknown theme '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
@@ -1,12 +1,20 @@
 use super::consts::*;
-use crate::game::consts::field::{Area, Row, FIELD_WIDTH};
-use crate::game::consts::qube::{Qube, QUBE_SIZE};
-use crate::game::consts::qubes::EMPTY_QUBE_ROW;
-use crate::game::geometry::Direction;
-use crate::game::object::Object;
-use crate::game::tetromino::Tetromino;
-use crate::game::Game;
-use crate::{empty_block, filled_block};
+use super::layout::Layout;
+use super::theme::Theme;
+use super::RenderSettings;
+use crate::input::bindings::{Action, KeyBindings};
+use crate::scores::{format_row, table_header, HighScore, MAX_HIGH_SCORES};
+use tetris_core::game::consts::field::Row;
+use tetris_core::game::consts::qube::{Qube, EMPTY_QUBE_ROW, QUBE_SIZE};
+use tetris_core::game::field::Field;
+use tetris_core::game::object::Object;
+use tetris_core::game::piece_set::PieceSet;
+use tetris_core::game::scoring::LineClear;
+use tetris_core::game::tetromino::Tetromino;
+use tetris_core::game::Game;
+use tetris_core::game::geometry::{Direction, Pos};
+use tetris_core::game::mode::ModeKind;
+use crate::{empty_block, filled_block, ghost_block};
 use crossterm::cursor::MoveTo;
 use crossterm::style::Color::{Red, White};
 use crossterm::style::{Color, Print, SetBackgroundColor, SetForegroundColor};
@@ -24,41 +32,61 @@ pub fn n_write(out: &mut impl Write, c: char, count: usize) -> io::Result<()> {
 }
 
 #[inline(always)]
-pub fn write_field_row(out: &mut impl Write, row: Row) -> io::Result<()> {
-    for i in (0..FIELD_WIDTH).rev() {
+pub fn write_field_row(
+    out: &mut impl Write,
+    row: Row,
+    width: u16,
+    theme: &Theme,
+) -> io::Result<()> {
+    for i in (0..width).rev() {
         if row & (1 << i) != 0 {
-            execute!(out, SetForegroundColor(FILLED_BLOCK_COLOR))?;
+            execute!(out, SetForegroundColor(theme.filled))?;
             write!(out, "{}", filled_block!())?;
         } else {
             // Reset color
-            execute!(out, SetForegroundColor(EMPTY_BLOCK_COLOR))?;
+            execute!(out, SetForegroundColor(theme.empty))?;
             write!(out, "{}", empty_block!())?;
         }
     }
     Ok(())
 }
 
+/// Writes the visible rows of the field, blanking the rest of the well around smaller fields.
 #[inline(always)]
-pub fn write_field(out: &mut impl Write, field: &Area) -> io::Result<()> {
-    for (y, row) in field.iter().enumerate() {
-        execute!(out, MoveTo(H_OFFSET + 1, V_OFFSET + y as u16 + 1))?;
-        write_field_row(out, *row)?;
+pub fn write_field(
+    out: &mut impl Write,
+    field: &Field,
+    layout: &Layout,
+    theme: &Theme,
+) -> io::Result<()> {
+    let (origin_x, origin_y) = layout.field_origin();
+    let padding = (origin_x - H_OFFSET - 1) as usize;
+
+    for y in V_OFFSET + 1..origin_y {
+        execute!(out, MoveTo(H_OFFSET + 1, y))?;
+        n_write(out, ' ', layout.well_width() as usize)?;
+    }
+    for (y, row) in (origin_y..).zip(&field.area[field.buffer as usize..]) {
+        execute!(out, MoveTo(H_OFFSET + 1, y))?;
+        n_write(out, ' ', padding)?;
+        write_field_row(out, *row, field.width, theme)?;
+        n_write(out, ' ', padding)?;
     }
 
     Ok(())
 }
 
 #[inline(always)]
-pub fn size_screen(out: &mut impl Write) -> io::Result<()> {
-    execute!(out, SetSize(SCREEN_WIDTH, SCREEN_HEIGHT))?;
+pub fn size_screen(out: &mut impl Write, layout: &Layout) -> io::Result<()> {
+    execute!(out, SetSize(layout.screen_width(), layout.screen_height()))?;
     Ok(())
 }
 
 #[inline(always)]
-pub fn clear_screen(out: &mut impl Write) -> io::Result<()> {
+pub fn clear_screen(out: &mut impl Write, layout: &Layout) -> io::Result<()> {
     execute!(
         out,
-        SetSize(SCREEN_WIDTH, SCREEN_HEIGHT),
+        SetSize(layout.screen_width(), layout.screen_height()),
         SetBackgroundColor(Color::Black),
         SetForegroundColor(Color::White),
         cursor::Hide,
@@ -70,98 +98,126 @@ pub fn clear_screen(out: &mut impl Write) -> io::Result<()> {
 
 /// Writes the top border by chaining write operations sequentially.
 #[inline(always)]
-pub fn write_top_border(out: &mut impl Write) -> io::Result<()> {
+pub fn write_top_border(out: &mut impl Write, layout: &Layout) -> io::Result<()> {
     execute!(out, MoveTo(H_OFFSET, V_OFFSET))?;
     write!(out, "{}", BORDER_TOP_LEFT)?;
-    n_write(out, BORDER_HORIZONTAL, AREA_WIDTH as usize)?;
+    n_write(out, BORDER_HORIZONTAL, layout.well_width() as usize)?;
     write!(out, "{}", BORDER_TOP_MIDDLE)?;
-    n_write(out, BORDER_HORIZONTAL, SIDEBAR_WIDTH as usize)?;
+    n_write(out, BORDER_HORIZONTAL, layout.column_width() as usize)?;
+    if layout.queue_column {
+        write!(out, "{}", BORDER_TOP_MIDDLE)?;
+        n_write(out, BORDER_HORIZONTAL, layout.column_width() as usize)?;
+    }
     write!(out, "{}", BORDER_TOP_RIGHT)?;
     Ok(())
 }
 
 #[inline(always)]
-pub fn write_bottom_border(out: &mut impl Write) -> io::Result<()> {
-    execute!(out, MoveTo(H_OFFSET, CONTAINER_HEIGHT + V_OFFSET - 1))?;
+pub fn write_bottom_border(out: &mut impl Write, layout: &Layout) -> io::Result<()> {
+    execute!(out, MoveTo(H_OFFSET, layout.container_height() + V_OFFSET - 1))?;
     write!(out, "{}", BORDER_BOTTOM_LEFT)?;
-    n_write(out, BORDER_HORIZONTAL, AREA_WIDTH as usize)?;
+    n_write(out, BORDER_HORIZONTAL, layout.well_width() as usize)?;
     write!(out, "{}", BORDER_BOTTOM_MIDDLE)?;
-    n_write(out, BORDER_HORIZONTAL, SIDEBAR_WIDTH as usize)?;
+    n_write(out, BORDER_HORIZONTAL, layout.column_width() as usize)?;
+    if layout.queue_column {
+        write!(out, "{}", BORDER_BOTTOM_MIDDLE)?;
+        n_write(out, BORDER_HORIZONTAL, layout.column_width() as usize)?;
+    }
     write!(out, "{}", BORDER_BOTTOM_RIGHT)?;
     Ok(())
 }
 
 #[inline(always)]
-pub fn write_vertical_borders(out: &mut impl Write) -> io::Result<()> {
-    for y in V_OFFSET + 1..CONTAINER_HEIGHT + V_OFFSET - 1 {
+pub fn write_vertical_borders(out: &mut impl Write, layout: &Layout) -> io::Result<()> {
+    for y in V_OFFSET + 1..layout.container_height() + V_OFFSET - 1 {
         execute!(out, MoveTo(H_OFFSET, y))?;
         write!(out, "{}", BORDER_VERTICAL)?;
-        execute!(out, MoveTo(H_OFFSET + AREA_WIDTH + 1, y))?;
+        execute!(out, MoveTo(layout.sidebar_x() - 1, y))?;
         write!(out, "{}", BORDER_VERTICAL_SINGLE)?;
-        execute!(out, MoveTo(H_OFFSET + AREA_WIDTH + SIDEBAR_WIDTH + 2, y))?;
+        if layout.queue_column {
+            execute!(out, MoveTo(layout.queue_x() - 1, y))?;
+            write!(out, "{}", BORDER_VERTICAL_SINGLE)?;
+        }
+        execute!(out, MoveTo(H_OFFSET + layout.container_width() - 1, y))?;
         write!(out, "{}", BORDER_VERTICAL)?;
     }
 
     Ok(())
 }
 
-pub fn get_tetromino_color(tetromino: &Tetromino) -> Color {
-    match tetromino {
-        Tetromino::I => Color::Cyan,
-        Tetromino::J => Color::Blue,
-        Tetromino::L => Color::Yellow,
-        Tetromino::O => Color::Red,
-        Tetromino::S => Color::Green,
-        Tetromino::T => Color::Magenta,
-        Tetromino::Z => Color::White,
-    }
-}
-
+/// Writes the given block on every cell of the object placed at the position,
+/// leaving out the cells still hidden in the buffer above the field.
 #[inline(always)]
-pub fn write_current_object(out: &mut impl Write, obj: &Object) -> io::Result<()> {
-    // Set object color and position
-    let color = get_tetromino_color(&obj.tetromino);
-    execute!(out, SetForegroundColor(color))?;
-
+fn write_object_cells(
+    out: &mut impl Write,
+    (obj, pos): (&Object, &Pos),
+    layout: &Layout,
+    block: &str,
+) -> io::Result<()> {
     for (y, row) in obj.qube.iter().enumerate() {
         if *row == EMPTY_QUBE_ROW {
             continue;
         }
 
-        let pos_y = obj.pos.y + y as u16 + V_OFFSET + 1;
-        let mut pos_x = obj.pos.x * 2 + H_OFFSET + 1;
-
-        for i in (4..QUBE_SIZE + 4).rev() {
-            execute!(out, MoveTo(pos_x, pos_y))?;
-            if *row & (1 << i) != 0 {
-                write!(out, "{}", filled_block!())?;
+        for (x, i) in (0..QUBE_SIZE).rev().enumerate() {
+            if *row & (1 << i) == 0 {
+                continue;
+            }
+            if let Some((pos_x, pos_y)) = layout.cell_position(pos.x + x as u16, pos.y + y as u16)
+            {
+                execute!(out, MoveTo(pos_x, pos_y), Print(block))?;
             }
-            pos_x += 2;
         }
     }
 
     Ok(())
 }
 
+#[inline(always)]
+pub fn write_current_object(
+    out: &mut impl Write,
+    obj: &Object,
+    pieces: &PieceSet,
+    layout: &Layout,
+    theme: &Theme,
+) -> io::Result<()> {
+    let color = theme.get_piece_color(&pieces[obj.tetromino]);
+    execute!(out, SetForegroundColor(color))?;
+    write_object_cells(out, (obj, &obj.pos), layout, filled_block!())
+}
+
+/// Writes a dimmed outline of the object at the position where it would land.
+#[inline(always)]
+pub fn write_ghost_object(
+    out: &mut impl Write,
+    obj: &Object,
+    ghost_pos: &Pos,
+    layout: &Layout,
+    theme: &Theme,
+) -> io::Result<()> {
+    execute!(out, SetForegroundColor(theme.ghost))?;
+    write_object_cells(out, (obj, ghost_pos), layout, ghost_block!())
+}
+
+/// Writes the qube into a square of the given size, blanking the cells it leaves free.
 #[inline(always)]
 pub fn render_qube(
     out: &mut impl Write,
-    qube: &Qube,
-    color: Color,
+    (qube, size): (&Qube, u16),
+    (color, empty_color): (Color, Color),
     pos: (u16, u16),
 ) -> io::Result<()> {
     let mut x = pos.0;
-    let mut y = pos.1;
 
-    for row in qube.iter() {
-        for i in (4..QUBE_SIZE + 4).rev() {
+    for (y, row) in (pos.1..).zip(qube.iter().take(size as usize)) {
+        for i in (QUBE_SIZE - size..QUBE_SIZE).rev() {
             queue!(out, MoveTo(x, y))?;
             if *row & (1 << i) != 0 {
                 execute!(out, SetForegroundColor(color), Print(filled_block!()))?;
             } else {
                 execute!(
                     out,
-                    SetForegroundColor(EMPTY_BLOCK_COLOR),
+                    SetForegroundColor(empty_color),
                     Print(empty_block!())
                 )?;
             }
@@ -170,34 +226,81 @@ pub fn render_qube(
         }
 
         x = pos.0;
-        y += 1;
     }
 
     Ok(())
 }
 
+/// Writes the first piece of the preview queue under the "next" label,
+/// and the rest stacked in the queue column.
 #[inline(always)]
-pub fn write_next_object(
+pub fn write_next_objects<'a>(
     out: &mut impl Write,
-    tetromino: &Tetromino,
-    direction: Direction,
+    next_objects: impl Iterator<Item = &'a (Tetromino, Direction)>,
+    pieces: &PieceSet,
+    layout: &Layout,
+    theme: &Theme,
 ) -> io::Result<()> {
-    let color = get_tetromino_color(tetromino);
-    let start_x = H_OFFSET + AREA_WIDTH + 4;
+    let start_x = layout.sidebar_x() + 2;
     let start_y = V_OFFSET + 1;
 
     execute!(
         out,
-        SetForegroundColor(FILLED_BLOCK_COLOR),
+        SetForegroundColor(theme.filled),
         MoveTo(start_x, start_y),
         Print("next")
     )?;
 
+    for (idx, (tetromino, direction)) in next_objects.enumerate() {
+        let pos = if idx == 0 {
+            (layout.sidebar_x(), V_OFFSET + 2)
+        } else {
+            (layout.queue_x(), layout.queue_slot_y(idx))
+        };
+
+        let piece = &pieces[*tetromino];
+        render_qube(
+            out,
+            (&piece.qube(direction), layout.piece_size),
+            (theme.get_piece_color(piece), theme.empty),
+            pos,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[inline(always)]
+pub fn write_held_object(
+    out: &mut impl Write,
+    tetromino: Option<&Tetromino>,
+    can_hold: bool,
+    pieces: &PieceSet,
+    layout: &Layout,
+    theme: &Theme,
+) -> io::Result<()> {
+    let start_x = layout.sidebar_x() + 2;
+    let start_y = layout.hold_y();
+
+    execute!(
+        out,
+        SetForegroundColor(theme.filled),
+        MoveTo(start_x, start_y),
+        Print("hold")
+    )?;
+
+    let (qube, color) = match tetromino.map(|tetromino| &pieces[*tetromino]) {
+        // The held piece is greyed out while it cannot be swapped back in
+        Some(piece) if can_hold => (piece.qube(&Direction::Up), theme.get_piece_color(piece)),
+        Some(piece) => (piece.qube(&Direction::Up), theme.hold_locked),
+        None => ([EMPTY_QUBE_ROW; QUBE_SIZE as usize], theme.empty),
+    };
+
     render_qube(
         out,
-        &tetromino.get_cube_by_direction(&direction),
-        color,
-        (H_OFFSET + AREA_WIDTH + 2, V_OFFSET + 2),
+        (&qube, layout.piece_size),
+        (color, theme.empty),
+        (layout.sidebar_x(), start_y + 1),
     )?;
 
     Ok(())
@@ -209,56 +312,117 @@ fn write_centered_str(
     (text, width): (&str, u16),
     (x, y): (u16, u16),
 ) -> io::Result<()> {
-    let x = x + (width - text.len() as u16) / 2;
+    let x = x + width.saturating_sub(text.len() as u16) / 2;
     execute!(out, MoveTo(x, y), Print(text))?;
     Ok(())
 }
 
+/// Writes one of the stats the mode shows in the sidebar, in the given slot from the top.
+/// Both are padded to the sidebar width, so shorter values leave nothing behind.
 #[inline(always)]
-fn write_score(out: &mut impl Write, score: u64) -> io::Result<()> {
-    let (x, y) = (H_OFFSET + AREA_WIDTH + 2, V_OFFSET + 7);
-    let score_str = format!("{}", score);
+fn write_stat(
+    out: &mut impl Write,
+    layout: &Layout,
+    slot: u16,
+    (label, value): &(&str, String),
+) -> io::Result<()> {
+    let (x, y) = (layout.sidebar_x(), layout.stat_y(slot));
+    let width = layout.column_width() as usize;
 
     execute!(
         out,
         MoveTo(x + 1, y),
         SetForegroundColor(Color::White),
-        Print("Score"),
+        Print(format!("{:<1$}", label, width - 1)),
+        MoveTo(x, y + 1),
         SetForegroundColor(Color::Green),
+        Print(format!("{:^1$}", value, width)),
     )?;
 
-    write_centered_str(out, (score_str.as_str(), SIDEBAR_WIDTH), (x, y + 1))?;
-
     Ok(())
 }
 
 #[inline(always)]
-fn write_level(out: &mut impl Write, level: u8) -> io::Result<()> {
-    let (x, y) = (H_OFFSET + AREA_WIDTH + 2, V_OFFSET + 10);
-    let level_str = format!("{}", level);
+fn write_centered_modal(
+    out: &mut impl Write,
+    layout: &Layout,
+    width: u16,
+    height: u16,
+) -> io::Result<()> {
+    let x = (layout.screen_width() - width) / 2;
+    let y = (layout.screen_height() - height) / 2;
 
-    execute!(
-        out,
-        MoveTo(x + 1, y),
-        SetForegroundColor(Color::White),
-        Print("Level"),
-        SetForegroundColor(Color::Green),
-    )?;
+    n_write(out, ' ', width as usize)?;
+    for i in 0..height {
+        execute!(out, MoveTo(x, y + i))?;
+        n_write(out, ' ', width as usize)?;
+    }
 
-    write_centered_str(out, (level_str.as_str(), SIDEBAR_WIDTH), (x, y + 1))?;
+    Ok(())
+}
+
+/// Splits the text into lines no longer than the given width, breaking at spaces.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for word in text.split(' ') {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+
+    lines
+}
+
+/// Shows the rows cleared away at the top of the stack crumbling, the highest ones first,
+/// as far along as the animation is.
+#[inline(always)]
+fn write_stack_clear(
+    out: &mut impl Write,
+    (rows, progress): (u16, f64),
+    layout: &Layout,
+    theme: &Theme,
+) -> io::Result<()> {
+    let first_row = (rows as f64 * progress).round() as u16;
+    let (origin_x, origin_y) = layout.field_origin();
+
+    execute!(out, SetForegroundColor(theme.line_clear))?;
+    for y in first_row..rows.min(layout.field_height) {
+        execute!(out, MoveTo(origin_x, origin_y + y))?;
+        for _ in 0..layout.field_width {
+            write!(out, "{}", ghost_block!())?;
+        }
+    }
 
     Ok(())
 }
 
+/// Flashes the name of the last line clear over the upper part of the field.
 #[inline(always)]
-fn write_centered_modal(out: &mut impl Write, width: u16, height: u16) -> io::Result<()> {
-    let x = (SCREEN_WIDTH - width) / 2;
-    let y = (SCREEN_HEIGHT - height) / 2;
+fn write_line_clear(
+    out: &mut impl Write,
+    clear: &LineClear,
+    layout: &Layout,
+    theme: &Theme,
+) -> io::Result<()> {
+    let mut lines = wrap_words(&clear.title(), layout.well_width() as usize);
+    if clear.perfect_clear {
+        lines.push("PERFECT CLEAR".to_string());
+    }
+    if clear.combo > 0 {
+        lines.push(format!("{} COMBO", clear.combo));
+    }
 
-    n_write(out, ' ', width as usize)?;
-    for i in 0..height {
-        execute!(out, MoveTo(x, y + i))?;
-        n_write(out, ' ', width as usize)?;
+    let x = H_OFFSET + 1;
+    let y = layout.field_origin().1 + layout.field_height / 4;
+
+    execute!(out, SetForegroundColor(theme.line_clear))?;
+    for (i, line) in lines.iter().enumerate() {
+        write_centered_str(out, (line.as_str(), layout.well_width()), (x, y + i as u16))?;
     }
 
     Ok(())
@@ -278,27 +442,31 @@ const PAUSE_ASCII_ART: [&str; 3] = [
     "└─┘ └─┘ └─┘ └─┘ └─┘",
 ];
 
-const CONTROLS_TEXT: [&str; 8] = [
-    "R      - rotate",
-    "A      - move left",
-    "D      - move right",
-    "S      - move down",
-    "W      - drop down",
-    "Space  - pause / resume",
-    "Bspce  - restart",
-    "Ctrl+C - exit",
-];
+/// Lists the bound keys of every action, lined up behind the longest ones.
+fn controls_text(bindings: &KeyBindings) -> Vec<String> {
+    let mut controls = bindings.controls_text();
+    controls.push(("Ctrl+C".to_string(), "exit"));
+
+    let width = controls.iter().map(|(keys, _)| keys.len()).max().unwrap_or(0);
+    controls
+        .into_iter()
+        .map(|(keys, description)| format!("{:<width$} - {}", keys, description))
+        .collect()
+}
 
 #[inline(always)]
 fn write_pause_content(
     out: &mut impl Write,
+    layout: &Layout,
+    bindings: &KeyBindings,
     modal_width: u16,
     modal_height: u16,
 ) -> io::Result<()> {
-    let modal_x = (SCREEN_WIDTH.saturating_sub(modal_width)) / 2;
-    let modal_y = (SCREEN_HEIGHT.saturating_sub(modal_height)) / 2;
+    let modal_x = (layout.screen_width().saturating_sub(modal_width)) / 2;
+    let modal_y = (layout.screen_height().saturating_sub(modal_height)) / 2;
 
-    let content_height = PAUSE_ASCII_ART.len() as u16 + 2 + CONTROLS_TEXT.len() as u16;
+    let controls = controls_text(bindings);
+    let content_height = PAUSE_ASCII_ART.len() as u16 + 2 + controls.len() as u16;
 
     let start_y_content = modal_y + (modal_height.saturating_sub(content_height)) / 2;
 
@@ -317,7 +485,7 @@ fn write_pause_content(
     current_y += 2;
 
     execute!(out, SetForegroundColor(Color::White))?;
-    for line in CONTROLS_TEXT.iter() {
+    for line in controls.iter() {
         execute!(out, MoveTo(modal_x + 3, current_y))?;
         write!(out, "{}", line)?;
         current_y += 1;
@@ -331,14 +499,17 @@ fn write_pause_content(
 fn write_game_over_content(
     out: &mut impl Write,
     game: &Game,
+    layout: &Layout,
+    bindings: &KeyBindings,
     modal_width: u16,
     modal_height: u16,
 ) -> io::Result<()> {
-    let modal_x = (SCREEN_WIDTH.saturating_sub(modal_width)) / 2;
-    let modal_y = (SCREEN_HEIGHT.saturating_sub(modal_height)) / 2;
+    let modal_x = (layout.screen_width().saturating_sub(modal_width)) / 2;
+    let modal_y = (layout.screen_height().saturating_sub(modal_height)) / 2;
 
     let score_text = format!("Score: {}", game.score);
     let level_text = format!("Level: {}", game.level);
+    let seed_text = format!("Seed: {}", game.get_seed());
 
     let content_height = GG_ASCII_ART.len() as u16 + 1 + 1 + 1 + 1;
 
@@ -367,8 +538,14 @@ fn write_game_over_content(
     execute!(out, MoveTo(x - 1, current_y))?;
     write!(out, "{}", level_text)?;
 
-    current_y += 3;
-    let text = "BACKSPACE to restart";
+    current_y += 1;
+    let seed_len = seed_text.len() as u16;
+    let x = modal_x + (modal_width.saturating_sub(seed_len)) / 2;
+    execute!(out, SetForegroundColor(Color::DarkGrey), MoveTo(x, current_y))?;
+    write!(out, "{}", seed_text)?;
+
+    current_y += 2;
+    let text = format!("{} to restart", bindings.describe(Action::Restart).to_uppercase());
     let x = modal_x + (modal_width.saturating_sub(text.len() as u16)) / 2;
     execute!(out, SetForegroundColor(White), MoveTo(x, current_y))?;
     write!(out, "{}", text)?;
@@ -384,35 +561,264 @@ fn write_game_over_content(
     Ok(())
 }
 
-pub fn write_game(out: &mut impl Write, game: &Game) -> io::Result<()> {
-    write_field(out, &game.field.area)?;
-    write_current_object(out, &game.current_object)?;
-    write_next_object(out, &game.next_object.0, game.next_object.1)?;
-    write_score(out, game.score)?;
-    write_level(out, game.level)?;
+/// Shows the results of a game that reached the goal of its mode.
+#[inline(always)]
+fn write_clear_content(
+    out: &mut impl Write,
+    game: &Game,
+    layout: &Layout,
+    bindings: &KeyBindings,
+    modal_width: u16,
+    modal_height: u16,
+) -> io::Result<()> {
+    let modal_x = (layout.screen_width().saturating_sub(modal_width)) / 2;
+    let modal_y = (layout.screen_height().saturating_sub(modal_height)) / 2;
+
+    let mode = game.get_mode();
+    let results = mode.results(game);
+    let label_width = results.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let content_height = 1 + 2 + results.len() as u16 + 1 + 1 + 2 + 2;
+    let mut y = modal_y + modal_height.saturating_sub(content_height) / 2;
+
+    execute!(out, SetForegroundColor(Color::Yellow))?;
+    write_centered_str(out, (mode.clear_title(), modal_width), (modal_x, y))?;
+    y += 2;
+
+    execute!(out, SetForegroundColor(Color::Cyan))?;
+    for (label, value) in &results {
+        let line = format!("{:>width$}: {}", label, value, width = label_width);
+        write_centered_str(out, (line.as_str(), modal_width), (modal_x, y))?;
+        y += 1;
+    }
+
+    y += 1;
+    let seed_text = format!("Seed: {}", game.get_seed());
+    execute!(out, SetForegroundColor(Color::DarkGrey))?;
+    write_centered_str(out, (seed_text.as_str(), modal_width), (modal_x, y))?;
+
+    y += 2;
+    let text = format!("{} to play again", bindings.describe(Action::Restart).to_uppercase());
+    execute!(out, SetForegroundColor(White))?;
+    write_centered_str(out, (text.as_str(), modal_width), (modal_x, y))?;
+    write_centered_str(out, ("Ctrl + C to exit", modal_width), (modal_x, y + 1))?;
+
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Lists the modes to choose from before the first game, marking the selected one.
+pub fn write_mode_select(out: &mut impl Write, layout: &Layout, selected: usize) -> io::Result<()> {
+    let modal_width = (layout.screen_width() as f32 * 0.8) as u16;
+    let modal_height = ModeKind::ALL.len() as u16 * 2 + 7;
+    write_centered_modal(out, layout, modal_width, modal_height)?;
+
+    let x = (layout.screen_width().saturating_sub(modal_width)) / 2;
+    let mut y = (layout.screen_height().saturating_sub(modal_height)) / 2 + 1;
+
+    execute!(out, SetForegroundColor(Color::Yellow))?;
+    write_centered_str(out, ("SELECT MODE", modal_width), (x, y))?;
+    y += 2;
+
+    for (idx, mode) in ModeKind::ALL.iter().enumerate() {
+        let (marker, color) = if idx == selected {
+            ('>', Color::Green)
+        } else {
+            (' ', Color::White)
+        };
+        let name = format!("{} {}. {}", marker, idx + 1, mode.name().to_uppercase());
+        execute!(
+            out,
+            SetForegroundColor(color),
+            MoveTo(x + 2, y),
+            Print(name),
+            SetForegroundColor(Color::DarkGrey),
+            MoveTo(x + 7, y + 1),
+            Print(mode.description())
+        )?;
+        y += 2;
+    }
+
+    execute!(out, SetForegroundColor(Color::DarkGrey))?;
+    write_centered_str(out, ("UP/DOWN and ENTER", modal_width), (x, y + 1))?;
+
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Asks for the initials to put on the high score table, over the game over modal.
+pub fn write_name_entry(
+    out: &mut impl Write,
+    layout: &Layout,
+    (rank, result): (usize, &str),
+    initials: &str,
+) -> io::Result<()> {
+    let modal_width = (layout.screen_width() as f32 * 0.8) as u16;
+    let modal_height = 8;
+    write_centered_modal(out, layout, modal_width, modal_height)?;
+
+    let x = (layout.screen_width().saturating_sub(modal_width)) / 2;
+    let y = (layout.screen_height().saturating_sub(modal_height)) / 2 + 1;
+    let rank_text = format!("#{} with {}", rank + 1, result);
+    let initials_text = format!("Initials: {:_<3}", initials);
+
+    execute!(out, SetForegroundColor(Color::Yellow))?;
+    write_centered_str(out, ("NEW HIGH SCORE", modal_width), (x, y))?;
+    execute!(out, SetForegroundColor(Color::Cyan))?;
+    write_centered_str(out, (rank_text.as_str(), modal_width), (x, y + 2))?;
+    execute!(out, SetForegroundColor(Color::White))?;
+    write_centered_str(out, (initials_text.as_str(), modal_width), (x, y + 3))?;
+    execute!(out, SetForegroundColor(Color::DarkGrey))?;
+    write_centered_str(out, ("ENTER to save", modal_width), (x, y + 5))?;
+
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Asks whether to continue the game saved when the last session ended.
+pub fn write_continue_prompt(out: &mut impl Write, layout: &Layout, game: &Game) -> io::Result<()> {
+    let modal_width = (layout.screen_width() as f32 * 0.8) as u16;
+    let modal_height = 9;
+    write_centered_modal(out, layout, modal_width, modal_height)?;
+
+    let x = (layout.screen_width().saturating_sub(modal_width)) / 2;
+    let y = (layout.screen_height().saturating_sub(modal_height)) / 2 + 1;
+    let score_text = format!("Score: {}", game.score);
+    let level_text = format!("Level: {}", game.level);
+
+    execute!(out, SetForegroundColor(Color::Yellow))?;
+    write_centered_str(out, ("CONTINUE SAVED GAME?", modal_width), (x, y))?;
+    execute!(out, SetForegroundColor(Color::Cyan))?;
+    write_centered_str(out, (score_text.as_str(), modal_width), (x, y + 2))?;
+    write_centered_str(out, (level_text.as_str(), modal_width), (x, y + 3))?;
+    execute!(out, SetForegroundColor(Color::White))?;
+    write_centered_str(out, ("Y - continue", modal_width), (x, y + 5))?;
+    write_centered_str(out, ("N - new game", modal_width), (x, y + 6))?;
+
+    out.flush()?;
+
+    Ok(())
+}
+
+/// Shows the high score table of a mode, highlighting the entry at the given rank.
+pub fn write_high_scores(
+    out: &mut impl Write,
+    layout: &Layout,
+    mode: &str,
+    table: &[HighScore],
+    highlight: Option<usize>,
+) -> io::Result<()> {
+    let modal_width = layout.screen_width() - 2;
+    let modal_height = MAX_HIGH_SCORES as u16 + 8;
+    write_centered_modal(out, layout, modal_width, modal_height)?;
+
+    let x = (layout.screen_width().saturating_sub(modal_width)) / 2;
+    let mut y = (layout.screen_height().saturating_sub(modal_height)) / 2 + 1;
+    let title = format!("HIGH SCORES - {}", mode.to_uppercase());
+
+    execute!(out, SetForegroundColor(Color::Yellow))?;
+    write_centered_str(out, (title.as_str(), modal_width), (x, y))?;
+    y += 2;
+
+    // Dates only fit next to the preview queue
+    let show_date = layout.queue_column;
+    let header = table_header(mode, show_date);
+    execute!(out, SetForegroundColor(Color::DarkGrey), MoveTo(x + 1, y), Print(header))?;
+    y += 1;
+
+    for rank in 0..MAX_HIGH_SCORES {
+        let line = match table.get(rank) {
+            Some(high_score) => format_row(mode, rank, high_score, show_date),
+            None => format!("{:>2}. ---", rank + 1),
+        };
+        let color = if highlight == Some(rank) {
+            Color::Green
+        } else {
+            Color::White
+        };
+        execute!(out, SetForegroundColor(color), MoveTo(x + 1, y), Print(line))?;
+        y += 1;
+    }
+
+    execute!(out, SetForegroundColor(Color::DarkGrey))?;
+    write_centered_str(out, ("Any key to go back", modal_width), (x, y + 1))?;
+
+    out.flush()?;
+
+    Ok(())
+}
+
+pub fn write_game(out: &mut impl Write, game: &Game, settings: &RenderSettings) -> io::Result<()> {
+    let layout = Layout::new(&game.rules);
+
+    let theme = &settings.theme;
+    write_field(out, &game.field, &layout, theme)?;
+    if let Some(stack_clear) = game.get_stack_clear() {
+        write_stack_clear(out, stack_clear, &layout, theme)?;
+    }
+    if settings.show_ghost && !game.game_over {
+        let ghost_pos = game.ghost_position();
+        write_ghost_object(out, &game.current_object, &ghost_pos, &layout, theme)?;
+    }
+    write_current_object(out, &game.current_object, &game.rules.pieces, &layout, theme)?;
+    if let Some(clear) = &game.last_clear {
+        write_line_clear(out, clear, &layout, theme)?;
+    }
+    if settings.show_preview {
+        let next_objects = game.next_objects.iter();
+        write_next_objects(out, next_objects, &game.rules.pieces, &layout, theme)?;
+    }
+    write_held_object(
+        out,
+        game.held_object.as_ref(),
+        game.can_hold_current_object(),
+        &game.rules.pieces,
+        &layout,
+        theme,
+    )?;
+    for (slot, stat) in game.get_mode().hud(game).iter().enumerate() {
+        write_stat(out, &layout, slot as u16, stat)?;
+    }
 
     if game.game_over {
-        let modal_width = (SCREEN_WIDTH as f32 * 0.8) as u16;
-        let modal_height = (SCREEN_HEIGHT as f32 * 0.7) as u16;
-        write_centered_modal(out, modal_width, modal_height)?;
-        write_game_over_content(out, game, modal_width, modal_height)?;
+        let modal_width = (layout.screen_width() as f32 * 0.8) as u16;
+        let modal_height = (layout.screen_height() as f32 * 0.7) as u16;
+        write_centered_modal(out, &layout, modal_width, modal_height)?;
+        let bindings = &settings.bindings;
+        if game.is_cleared() {
+            write_clear_content(out, game, &layout, bindings, modal_width, modal_height)?;
+        } else {
+            write_game_over_content(out, game, &layout, bindings, modal_width, modal_height)?;
+        }
     }
 
     if game.is_paused() {
-        let modal_width = (SCREEN_WIDTH as f32 * 0.8) as u16;
-        let modal_height = (SCREEN_HEIGHT as f32 * 0.8) as u16;
+        let modal_width = (layout.screen_width() as f32 * 0.8) as u16;
+        let modal_height = (layout.screen_height() as f32 * 0.8) as u16;
 
-        write_centered_modal(out, modal_width, modal_height)?;
-        write_pause_content(out, modal_width, modal_height)?;
+        write_centered_modal(out, &layout, modal_width, modal_height)?;
+        write_pause_content(out, &layout, &settings.bindings, modal_width, modal_height)?;
     }
 
     Ok(())
 }
 
-pub fn write_border(out: &mut impl Write) -> io::Result<()> {
-    write_top_border(out)?;
-    write_bottom_border(out)?;
-    write_vertical_borders(out)?;
+/// Writes a short status text into the top border above the field, replacing the previous one.
+pub fn write_status(out: &mut impl Write, layout: &Layout, text: &str) -> io::Result<()> {
+    execute!(out, SetForegroundColor(Color::White))?;
+    write_top_border(out, layout)?;
+    write_centered_str(out, (text, layout.well_width() + 2), (H_OFFSET, V_OFFSET))?;
+    out.flush()?;
+
+    Ok(())
+}
+
+pub fn write_border(out: &mut impl Write, layout: &Layout) -> io::Result<()> {
+    write_top_border(out, layout)?;
+    write_bottom_border(out, layout)?;
+    write_vertical_borders(out, layout)?;
 
     Ok(())
 }
@@ -0,0 +1,194 @@
+use super::{now, HighScore, HighScores, MAX_NAME_LEN};
+use tetris_core::game::events::GameEvent;
+use tetris_core::game::mode::{format_timer, Ranking};
+use tetris_core::game::rendering::Renderer as _;
+use tetris_core::game::Game;
+use crate::renderer::layout::Layout;
+use crate::renderer::writers::{write_high_scores, write_name_entry};
+use crate::renderer::{RenderSettings, Renderer};
+use crossterm::event::KeyCode;
+use std::fs;
+use std::io::stdout;
+use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+
+/// What the score board shows on top of the game.
+enum Screen {
+    Hidden,
+    /// Asking for the initials of a game that made it onto the table
+    NameEntry { rank: usize, initials: String },
+    /// The table itself, with the rank of a new entry to highlight
+    Table { highlight: Option<usize> },
+}
+
+/// High score table of the mode being played, with the screens to enter and view it.
+pub struct ScoreBoard {
+    scores: HighScores,
+    events: Receiver<GameEvent>,
+    screen: Screen,
+    /// Draws the game again once a screen of the board is closed
+    renderer: Renderer,
+    /// Problems saving the high scores, to report once the terminal is restored
+    errors: Vec<String>,
+}
+
+impl ScoreBoard {
+    /// `events` should receive the events of the game played, which is drawn with `settings`.
+    pub fn new(scores: HighScores, events: Receiver<GameEvent>, settings: RenderSettings) -> Self {
+        ScoreBoard {
+            scores,
+            events,
+            screen: Screen::Hidden,
+            renderer: Renderer::new(settings),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Whether one of the board's screens is shown, which then takes all key presses.
+    pub fn is_shown(&self) -> bool {
+        !matches!(self.screen, Screen::Hidden)
+    }
+
+    /// Looks for the end of the game, asking for initials if it made it onto the table.
+    /// Modes ranked by time only count games that reached their goal.
+    pub fn poll_events(&mut self, game: &Game) {
+        let ended = self
+            .events
+            .try_iter()
+            .any(|event| matches!(event, GameEvent::GameOver { .. } | GameEvent::Cleared));
+        let counts = game.is_cleared() || game.get_mode().ranking() == Ranking::Score;
+        if !ended || !counts {
+            return;
+        }
+
+        let mode = game.rules.mode.name();
+        let high_score = high_score_of(game, "");
+        if self.scores.qualifies(mode, &high_score) {
+            let rank = self.scores.rank_of(mode, &high_score);
+            self.screen = Screen::NameEntry {
+                rank,
+                initials: String::new(),
+            };
+            self.draw(game);
+        }
+    }
+
+    /// Shows the high score table.
+    pub fn show_table(&mut self, game: &Game) {
+        self.screen = Screen::Table { highlight: None };
+        self.draw(game);
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode, game: &Game) {
+        match &mut self.screen {
+            Screen::Hidden => {}
+            Screen::NameEntry { initials, .. } => match code {
+                KeyCode::Enter => self.save(game),
+                KeyCode::Backspace => {
+                    initials.pop();
+                    self.draw(game);
+                }
+                KeyCode::Char(c) if c.is_ascii_alphanumeric() && initials.len() < MAX_NAME_LEN => {
+                    initials.push(c.to_ascii_uppercase());
+                    self.draw(game);
+                }
+                _ => {}
+            },
+            Screen::Table { .. } => {
+                self.screen = Screen::Hidden;
+                self.renderer.prerender(game);
+                self.renderer.render(game);
+            }
+        }
+    }
+
+    /// Saves a game still waiting for its initials, so quitting does not lose it.
+    pub fn finish(&mut self, game: &Game) {
+        if matches!(self.screen, Screen::NameEntry { .. }) {
+            self.save(game);
+        }
+    }
+
+    /// Problems met saving high scores so far.
+    pub fn take_errors(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Puts the game onto the table under the typed initials and shows the table.
+    fn save(&mut self, game: &Game) {
+        let Screen::NameEntry { initials, .. } = &self.screen else {
+            return;
+        };
+
+        let mut high_score = high_score_of(game, initials);
+        high_score.replay = self.save_replay(game, high_score.date);
+
+        let highlight = self.scores.insert(game.rules.mode.name(), high_score);
+        if let Err(error) = self.scores.save() {
+            self.errors.push(format!(
+                "Failed to save the high scores to {}: {}",
+                self.scores.get_path().display(),
+                error
+            ));
+        }
+
+        self.screen = Screen::Table { highlight };
+        self.draw(game);
+    }
+
+    /// Keeps a replay of the game next to the high scores, returning where it went.
+    fn save_replay(&mut self, game: &Game, date: u64) -> Option<PathBuf> {
+        let dir = self.scores.get_path().parent()?.join("replays");
+        let mode = game.rules.mode.name();
+        let path = dir.join(format!("{}-{}-{}.replay", mode, date, game.score));
+        let saved = fs::create_dir_all(&dir);
+        match saved.and_then(|_| fs::write(&path, game.replay().to_bytes())) {
+            Ok(()) => Some(path),
+            Err(error) => {
+                self.errors.push(format!(
+                    "Failed to save the replay to {}: {}",
+                    path.display(),
+                    error
+                ));
+                None
+            }
+        }
+    }
+
+    fn draw(&self, game: &Game) {
+        let out = &mut stdout();
+        let layout = Layout::new(&game.rules);
+        let mode = game.rules.mode.name();
+        let result = match &self.screen {
+            Screen::Hidden => Ok(()),
+            Screen::NameEntry { rank, initials } => {
+                let result = match game.get_mode().ranking() {
+                    Ranking::Score => game.score.to_string(),
+                    Ranking::Time => format_timer(game.get_time()),
+                };
+                write_name_entry(out, &layout, (*rank, &result), initials)
+            }
+            Screen::Table { highlight } => write_high_scores(
+                out,
+                &layout,
+                mode,
+                self.scores.get_table(mode),
+                *highlight,
+            ),
+        };
+        result.expect("Failed to write high scores");
+    }
+}
+
+/// Entry of the game on the high score table under the given initials, without a replay.
+fn high_score_of(game: &Game, initials: &str) -> HighScore {
+    HighScore {
+        name: HighScore::clean_name(initials),
+        score: game.score,
+        level: game.level,
+        lines: game.lines,
+        duration: game.get_time(),
+        date: now(),
+        replay: None,
+    }
+}
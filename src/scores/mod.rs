@@ -0,0 +1,393 @@
+use tetris_core::game::mode::{format_timer, ModeKind, Ranking};
+use std::collections::BTreeMap;
+use crate::storage::write_atomically;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub mod board;
+
+/// Best games kept per mode.
+pub const MAX_HIGH_SCORES: usize = 10;
+/// Most characters a name on the table may have.
+pub const MAX_NAME_LEN: usize = 3;
+/// Name stored when the player confirms without typing any initials.
+pub const ANONYMOUS_NAME: &str = "---";
+
+/// Name of the high score file in the data directory.
+pub const SCORES_FILE: &str = "scores.txt";
+
+/// First line of the high score file, telling its format apart from anything else.
+const FILE_HEADER: &str = "# tetris-console-game high scores v1";
+
+/// A finished game on the high score table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u64,
+    pub level: u8,
+    pub lines: u32,
+    /// Time played, not counting pauses.
+    pub duration: Duration,
+    /// Seconds since the Unix epoch the game ended at.
+    pub date: u64,
+    /// Replay of the game, if it could be saved.
+    pub replay: Option<PathBuf>,
+}
+
+impl HighScore {
+    /// Keeps the letters and digits of the given initials, uppercased.
+    pub fn clean_name(initials: &str) -> String {
+        let name: String = initials
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .take(MAX_NAME_LEN)
+            .collect::<String>()
+            .to_ascii_uppercase();
+        if name.is_empty() {
+            ANONYMOUS_NAME.to_string()
+        } else {
+            name
+        }
+    }
+
+    /// Key ordering the table of a mode, best games first: the highest score, or the fastest
+    /// time in modes ranked by time, with the other one breaking ties.
+    fn sort_key(&self, ranking: Ranking) -> (u64, u64) {
+        let time = self.duration.as_millis() as u64;
+        match ranking {
+            Ranking::Score => (u64::MAX - self.score, time),
+            Ranking::Time => (time, u64::MAX - self.score),
+        }
+    }
+
+    fn to_line(&self, mode: &str) -> String {
+        let replay = self
+            .replay
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            mode,
+            self.name,
+            self.score,
+            self.level,
+            self.lines,
+            self.duration.as_millis(),
+            self.date,
+            replay
+        )
+    }
+
+    /// Reads a line written by `to_line`, or nothing if it is damaged.
+    fn from_line(line: &str) -> Option<(String, HighScore)> {
+        let mut columns = line.splitn(8, '\t');
+        let mode = columns.next().filter(|mode| !mode.is_empty())?.to_string();
+        let name = columns.next()?;
+        let high_score = HighScore {
+            name: HighScore::clean_name(name),
+            score: columns.next()?.parse().ok()?,
+            level: columns.next()?.parse().ok()?,
+            lines: columns.next()?.parse().ok()?,
+            duration: Duration::from_millis(columns.next()?.parse().ok()?),
+            date: columns.next()?.parse().ok()?,
+            replay: columns
+                .next()
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+        };
+        Some((mode, high_score))
+    }
+}
+
+/// High score tables of all modes, kept in a single file.
+pub struct HighScores {
+    path: PathBuf,
+    tables: BTreeMap<String, Vec<HighScore>>,
+}
+
+impl HighScores {
+    /// Reads the high scores from the given file. Missing files give empty tables
+    /// and damaged lines are skipped, so a broken file never keeps the game from starting.
+    pub fn load(path: PathBuf) -> Self {
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        HighScores {
+            path,
+            tables: parse_tables(&text),
+        }
+    }
+
+    /// Writes the high scores, replacing the old file at once so it is never left half written.
+    pub fn save(&self) -> io::Result<()> {
+        let mut text = String::from(FILE_HEADER);
+        text.push('\n');
+        for (mode, table) in &self.tables {
+            for high_score in table {
+                text.push_str(&high_score.to_line(mode));
+                text.push('\n');
+            }
+        }
+
+        write_atomically(&self.path, text.as_bytes())
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Modes that have a table.
+    pub fn get_modes(&self) -> impl Iterator<Item = &str> {
+        self.tables.keys().map(String::as_str)
+    }
+
+    /// Best games of the given mode, best first.
+    pub fn get_table(&self, mode: &str) -> &[HighScore] {
+        self.tables.get(mode).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether the game makes it onto the table of the mode.
+    pub fn qualifies(&self, mode: &str, high_score: &HighScore) -> bool {
+        let ranking = ranking(mode);
+        let table = self.get_table(mode);
+        let beats_last = table
+            .last()
+            .is_some_and(|last| high_score.sort_key(ranking) < last.sort_key(ranking));
+        let counts = match ranking {
+            Ranking::Score => high_score.score > 0,
+            Ranking::Time => !high_score.duration.is_zero(),
+        };
+        counts && (table.len() < MAX_HIGH_SCORES || beats_last)
+    }
+
+    /// Rank the game would get on the table of the mode, counted from 0.
+    /// Games tied with earlier ones rank below them.
+    pub fn rank_of(&self, mode: &str, high_score: &HighScore) -> usize {
+        let key = high_score.sort_key(ranking(mode));
+        self.get_table(mode)
+            .partition_point(|other| other.sort_key(ranking(mode)) <= key)
+    }
+
+    /// Puts the game onto the table of the mode, returning its rank counted from 0.
+    pub fn insert(&mut self, mode: &str, high_score: HighScore) -> Option<usize> {
+        if !self.qualifies(mode, &high_score) {
+            return None;
+        }
+
+        let rank = self.rank_of(mode, &high_score);
+        let table = self.tables.entry(mode.to_string()).or_default();
+        table.insert(rank, high_score);
+        table.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+}
+
+fn parse_tables(text: &str) -> BTreeMap<String, Vec<HighScore>> {
+    let mut tables: BTreeMap<String, Vec<HighScore>> = BTreeMap::new();
+    for (mode, high_score) in text
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(HighScore::from_line)
+    {
+        tables.entry(mode).or_default().push(high_score);
+    }
+
+    // The file may have been edited by hand, so its order is not trusted
+    for (mode, table) in tables.iter_mut() {
+        let ranking = ranking(mode);
+        table.sort_by_key(|high_score| high_score.sort_key(ranking));
+        table.truncate(MAX_HIGH_SCORES);
+    }
+    tables
+}
+
+/// What the table of the mode is ordered by. Tables of unknown modes are ranked by score.
+pub fn ranking(mode: &str) -> Ranking {
+    mode.parse::<ModeKind>()
+        .map_or(Ranking::Score, |kind| kind.create().ranking())
+}
+
+/// Column titles of the table of the mode, lined up with `format_row`.
+pub fn table_header(mode: &str, show_date: bool) -> String {
+    let header = match ranking(mode) {
+        Ranking::Score => "    NAME    SCORE LINES  TIME",
+        Ranking::Time => "    NAME      TIME    SCORE",
+    };
+    if show_date {
+        format!("{}       DATE", header)
+    } else {
+        header.to_string()
+    }
+}
+
+/// Line of the table of the mode showing the game at the given rank, counted from 0.
+pub fn format_row(mode: &str, rank: usize, high_score: &HighScore, show_date: bool) -> String {
+    let mut line = match ranking(mode) {
+        Ranking::Score => format!(
+            "{:>2}. {:<3} {:>8} {:>5} {:>5}",
+            rank + 1,
+            high_score.name,
+            high_score.score,
+            high_score.lines,
+            format_duration(high_score.duration)
+        ),
+        Ranking::Time => format!(
+            "{:>2}. {:<3} {:>9} {:>8}",
+            rank + 1,
+            high_score.name,
+            format_timer(high_score.duration),
+            high_score.score
+        ),
+    };
+    if show_date {
+        line.push_str(&format!(" {:>10}", format_date(high_score.date)));
+    }
+    line
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+/// Formats seconds since the Unix epoch as a `YYYY-MM-DD` date in UTC.
+pub fn format_date(secs: u64) -> String {
+    // Converts days to a civil date, counting in 400 year eras starting on March 1st
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Formats a duration as minutes and seconds, such as `3:07`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn high_score(name: &str, score: u64) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            score,
+            level: 3,
+            lines: 25,
+            duration: Duration::from_millis(187_250),
+            date: 1_760_745_600,
+            replay: None,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir()
+            .join(format!("tetris-scores-test-{}", std::process::id()))
+            .join(name)
+    }
+
+    #[test]
+    fn test_insert_keeps_best_scores_in_order() {
+        let mut scores = HighScores::load(temp_path("unused.txt"));
+        for score in 1..=MAX_HIGH_SCORES as u64 {
+            assert!(scores.insert("endless", high_score("AAA", score * 100)).is_some());
+        }
+
+        assert!(!scores.qualifies("endless", &high_score("DDD", 100)));
+        assert_eq!(scores.insert("endless", high_score("BBB", 550)), Some(5));
+        assert_eq!(scores.insert("endless", high_score("CCC", 550)), Some(6));
+
+        let table = scores.get_table("endless");
+        assert_eq!(table.len(), MAX_HIGH_SCORES);
+        assert_eq!(table[0].score, 1000);
+        assert_eq!(table[5].name, "BBB");
+        assert_eq!(table[6].name, "CCC");
+        assert_eq!(table[MAX_HIGH_SCORES - 1].score, 300);
+
+        assert!(scores.get_table("marathon").is_empty());
+        assert!(!scores.qualifies("marathon", &high_score("DDD", 0)));
+    }
+
+    #[test]
+    fn test_sprint_ranks_fastest_first() {
+        let mut scores = HighScores::load(temp_path("unused.txt"));
+        let mut fast = high_score("AAA", 100);
+        fast.duration = Duration::from_millis(61_500);
+        let mut slow = high_score("BBB", 9000);
+        slow.duration = Duration::from_millis(75_250);
+
+        assert_eq!(scores.insert("sprint", slow), Some(0));
+        assert_eq!(scores.insert("sprint", fast), Some(0));
+        assert_eq!(scores.get_table("sprint")[1].name, "BBB");
+        assert_eq!(
+            format_row("sprint", 0, &scores.get_table("sprint")[0], false),
+            " 1. AAA  1:01.500      100"
+        );
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = temp_path("round-trip.txt");
+        let mut scores = HighScores::load(path.clone());
+        scores.insert("endless", high_score("ABC", 1200));
+        let mut with_replay = high_score("XYZ", 900);
+        with_replay.replay = Some(PathBuf::from("replays/endless 1.replay"));
+        scores.insert("nes", with_replay.clone());
+        scores.save().unwrap();
+
+        let loaded = HighScores::load(path.clone());
+        assert_eq!(loaded.get_table("endless"), &[high_score("ABC", 1200)]);
+        assert_eq!(loaded.get_table("nes"), &[with_replay]);
+        assert!(!path.with_extension("tmp").exists());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_damaged_lines_are_skipped() {
+        let text = "# tetris-console-game high scores v1\n\
+                    endless\tABC\t500\t2\t12\t60000\t1760745600\t\n\
+                    endless\tDEF\tlots\t2\t12\t60000\t1760745600\t\n\
+                    \u{0}\u{1}garbage\n\
+                    endless\tGHI\t700\t3\t20\t90000\n\
+                    endless\tj k\t900\t4\t31\t120000\t1760745600\t\n";
+        let tables = parse_tables(text);
+
+        let table = &tables["endless"];
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[0].name, "JK");
+        assert_eq!(table[1].name, "ABC");
+
+        assert!(HighScores::load(temp_path("missing.txt"))
+            .get_table("endless")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_clean_name() {
+        assert_eq!(HighScore::clean_name("ab"), "AB");
+        assert_eq!(HighScore::clean_name("x-y\tz9"), "XYZ");
+        assert_eq!(HighScore::clean_name(" "), ANONYMOUS_NAME);
+    }
+
+    #[test]
+    fn test_format_date_and_duration() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_760_745_600), "2025-10-18");
+        assert_eq!(format_duration(Duration::from_millis(187_250)), "3:07");
+    }
+}
@@ -0,0 +1,58 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directory high scores, replays and saved games are kept in, following the XDG base
+/// directories. Falls back to the working directory if no home directory is known.
+pub fn data_dir() -> PathBuf {
+    let non_empty = |name| env::var_os(name).filter(|value| !value.is_empty());
+    non_empty("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .or_else(|| non_empty("APPDATA").map(PathBuf::from))
+        .map(|base| base.join("tetris-console-game"))
+        .unwrap_or_default()
+}
+
+/// Directory the config file is read from, following the XDG base directories.
+/// Falls back to the working directory if no home directory is known.
+pub fn config_dir() -> PathBuf {
+    let non_empty = |name| env::var_os(name).filter(|value| !value.is_empty());
+    non_empty("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| non_empty("HOME").map(|home| Path::new(&home).join(".config")))
+        .or_else(|| non_empty("APPDATA").map(PathBuf::from))
+        .map(|base| base.join("tetris-console-game"))
+        .unwrap_or_default()
+}
+
+/// Writes the file through a temporary file, which then replaces the old one at once,
+/// so a crash or full disk never leaves it half written.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomically_replaces_file() {
+        let dir = env::temp_dir().join(format!("tetris-storage-test-{}", std::process::id()));
+        let path = dir.join("nested").join("file.txt");
+
+        write_atomically(&path, b"first").unwrap();
+        write_atomically(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert!(!path.with_extension("tmp").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,220 @@
+//! A simple bot, placing every piece where it leaves the flattest stack.
+//! It plays through `Game::apply` like a player would, so its games can be recorded and replayed.
+
+use super::field::Field;
+use super::geometry::{Direction, Pos};
+use super::input::Input;
+use super::tetromino::Tetromino;
+use super::Game;
+
+/// How much each feature of the stack left behind by a placement counts.
+/// Positive weights are rewarded, negative ones are penalties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BotWeights {
+    /// Per cell of the summed up column heights
+    pub height: f64,
+    pub lines: f64,
+    /// Per empty cell with a filled cell somewhere above it
+    pub holes: f64,
+    /// Per cell of height difference between neighbouring columns
+    pub bumpiness: f64,
+}
+
+impl Default for BotWeights {
+    fn default() -> Self {
+        BotWeights {
+            height: -0.51,
+            lines: 0.76,
+            holes: -0.36,
+            bumpiness: -0.18,
+        }
+    }
+}
+
+/// Where the bot wants the current object to go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Target {
+    direction: Direction,
+    x: u16,
+}
+
+/// Steers each piece into place one input at a time.
+pub struct Bot {
+    weights: BotWeights,
+    target: Option<Target>,
+    /// Piece, direction and position the last input was given for, to notice when it did nothing
+    last_state: Option<(Tetromino, Direction, u16, u16)>,
+}
+
+impl Bot {
+    pub fn new(weights: BotWeights) -> Self {
+        Bot {
+            weights,
+            target: None,
+            last_state: None,
+        }
+    }
+
+    /// Next input bringing the current object to the best placement.
+    /// Meant to be called once per frame, applying the input before the frame is played.
+    pub fn next_input(&mut self, game: &Game) -> Input {
+        let object = &game.current_object;
+        let pos = *object.get_position();
+        let state = (*object.get_type(), *object.get_direction(), pos.x, pos.y);
+
+        // A new piece spawned above the last one, maybe locked by gravity before it was dropped
+        let new_piece = self
+            .last_state
+            .is_some_and(|(tetromino, _, _, y)| tetromino != state.0 || y > state.3);
+        // The last input could not move the piece, so the target cannot be reached
+        let stuck = self.last_state == Some(state);
+        if new_piece {
+            self.target = None;
+        }
+        self.last_state = Some(state);
+
+        let target = match self.target {
+            Some(target) => target,
+            None => *self.target.insert(self.best_target(game)),
+        };
+        let direction = state.1;
+        let input = if stuck {
+            Input::HardDrop
+        } else if target.direction == direction.copy_rotate() {
+            Input::RotateCw
+        } else if target.direction == direction.copy_rotate_ccw() {
+            Input::RotateCcw
+        } else if target.direction != direction {
+            Input::Rotate180
+        } else if target.x < pos.x {
+            Input::MoveLeft
+        } else if target.x > pos.x {
+            Input::MoveRight
+        } else {
+            Input::HardDrop
+        };
+
+        if input == Input::HardDrop {
+            self.target = None;
+            self.last_state = None;
+        }
+        input
+    }
+
+    /// Tries every direction and column the current object can drop from, keeping the placement
+    /// that leaves the best stack. Directions needing fewer rotations win ties.
+    fn best_target(&self, game: &Game) -> Target {
+        let object = &game.current_object;
+        let piece = &game.rules.pieces[*object.get_type()];
+        let current = *object.get_direction();
+        let start_y = object.get_position().y;
+
+        let mut best: Option<(f64, Target)> = None;
+        let mut field = game.field.clone();
+        let directions = [
+            current,
+            current.copy_rotate(),
+            current.copy_rotate_ccw(),
+            current.copy_rotate_180(),
+        ];
+        for direction in directions {
+            let qube = piece.qube(&direction);
+            let (width, _) = piece.dimensions(&direction);
+            for x in 0..=game.field.width.saturating_sub(width) {
+                let mut pos = Pos { x, y: start_y };
+                if !game.field.can_hold((&qube, width), &pos) {
+                    continue;
+                }
+                while game.field.can_hold((&qube, width), &pos.copy_mod_y(1)) {
+                    pos.mod_y(1);
+                }
+
+                field.area.clone_from(&game.field.area);
+                if field.place((&qube, width), &pos).is_err() {
+                    continue;
+                }
+                let lines = field.clear_completed_lines();
+                let score = self.evaluate(&field, lines);
+                if best.is_none_or(|(best_score, _)| score > best_score) {
+                    best = Some((score, Target { direction, x }));
+                }
+            }
+        }
+
+        best.map_or(
+            Target {
+                direction: current,
+                x: object.get_position().x,
+            },
+            |(_, target)| target,
+        )
+    }
+
+    fn evaluate(&self, field: &Field, lines: u16) -> f64 {
+        let width = field.width as i16;
+        let height = field.rows() as i16;
+        let mut heights = Vec::with_capacity(field.width as usize);
+        let mut holes = 0;
+
+        for x in 0..width {
+            let top = (0..height).find(|&y| field.is_occupied(x, y)).unwrap_or(height);
+            holes += (top..height).filter(|&y| !field.is_occupied(x, y)).count();
+            heights.push((height - top) as f64);
+        }
+
+        let total_height: f64 = heights.iter().sum();
+        let bumpiness: f64 = heights.windows(2).map(|pair| (pair[0] - pair[1]).abs()).sum();
+        self.weights.height * total_height
+            + self.weights.lines * lines as f64
+            + self.weights.holes * holes as f64
+            + self.weights.bumpiness * bumpiness
+    }
+}
+
+impl Default for Bot {
+    fn default() -> Self {
+        Bot::new(BotWeights::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::piece_set::PieceSet;
+    use crate::game::rendering::NullRenderer;
+    use crate::game::rules::Rules;
+    use crate::game::FRAME_DURATION;
+
+    #[test]
+    fn test_bot_keeps_clearing_lines() {
+        let mut game = Game::new(Box::new(NullRenderer), Rules::default(), 7);
+        game.set_paused(false);
+        let mut bot = Bot::default();
+
+        while game.lines < 50 && !game.is_game_over() && game.get_frame() < 10_000 {
+            game.apply(bot.next_input(&game));
+            game.update(FRAME_DURATION);
+        }
+
+        assert!(!game.is_game_over());
+        assert!(game.lines >= 50, "cleared only {} lines", game.lines);
+        assert!(game.replay().verify().is_ok());
+    }
+
+    #[test]
+    fn test_evaluate_prefers_flat_stacks() {
+        let pieces = PieceSet::standard();
+        let bot = Bot::default();
+        let mut flat = Field::default();
+        let i_flat = pieces[Tetromino::I].qube(&Direction::Up);
+        let (width, _) = pieces[Tetromino::I].dimensions(&Direction::Up);
+        flat.place((&i_flat, width), &Pos { x: 0, y: 21 }).unwrap();
+
+        let mut tower = Field::default();
+        let i_tall = pieces[Tetromino::I].qube(&Direction::Right);
+        let (width, _) = pieces[Tetromino::I].dimensions(&Direction::Right);
+        tower.place((&i_tall, width), &Pos { x: 0, y: 18 }).unwrap();
+
+        assert!(bot.evaluate(&flat, 0) > bot.evaluate(&tower, 0));
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of time for `Game::tick`. The game itself only ever moves on by whole frames.
+pub trait Clock: Send + Sync {
+    /// Time passed since some fixed starting point, which only ever grows.
+    fn now(&self) -> Duration;
+}
+
+/// Clock following real time.
+pub struct WallClock {
+    started_at: Instant,
+}
+
+impl WallClock {
+    pub fn new() -> Self {
+        WallClock {
+            started_at: Instant::now(),
+        }
+    }
+}
+
+impl Default for WallClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for WallClock {
+    fn now(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+}
+
+/// Clock that only moves when told to, for tests and simulations.
+/// Clones share their time, so a clone handed to a game can still be advanced from outside.
+#[derive(Debug, Default, Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, dt: Duration) {
+        *self.now.lock().unwrap() += dt;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_clones_share_time() {
+        let clock = ManualClock::new();
+        let game_clock = clock.clone();
+        assert_eq!(game_clock.now(), Duration::ZERO);
+
+        clock.advance(Duration::from_millis(250));
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(game_clock.now(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_wall_clock_moves_forward() {
+        let clock = WallClock::new();
+        let before = clock.now();
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(clock.now() > before);
+    }
+}
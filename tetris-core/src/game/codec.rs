@@ -0,0 +1,281 @@
+//! Building blocks of the compact binary formats games are stored in, such as replays and saves.
+
+use super::geometry::Direction;
+use super::input::Input;
+use super::piece_set::PieceSet;
+use super::rules::Rules;
+use super::tetromino::Tetromino;
+use std::sync::Arc;
+
+/// Writes the number 7 bits at a time, lowest first, with the high bit telling whether more follow.
+pub fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub fn write_str(out: &mut Vec<u8>, value: &str) {
+    write_varint(out, value.len() as u64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+pub fn write_bytes(out: &mut Vec<u8>, value: &[u8]) {
+    write_varint(out, value.len() as u64);
+    out.extend_from_slice(value);
+}
+
+pub fn write_rules(out: &mut Vec<u8>, rules: &Rules) {
+    write_varint(out, rules.hold_penalty);
+    write_str(out, rules.randomizer.name());
+    write_varint(out, rules.lock_delay_ms.max(0) as u64);
+    out.push(rules.max_lock_resets);
+    write_str(out, rules.gravity.name());
+    out.push(rules.start_level);
+    write_varint(out, rules.lines_per_level as u64);
+    write_varint(out, rules.preview_size as u64);
+    write_varint(out, rules.field_width as u64);
+    write_varint(out, rules.field_height as u64);
+    write_varint(out, rules.field_buffer as u64);
+    write_str(out, rules.mode.name());
+    write_piece_set(out, &rules.pieces);
+}
+
+/// Writes the name of the piece set, and its text unless it is one the game ships with.
+fn write_piece_set(out: &mut Vec<u8>, pieces: &PieceSet) {
+    write_str(out, pieces.name());
+    write_str(out, if pieces.is_builtin() { "" } else { pieces.source() });
+}
+
+/// Writes inputs with the frame they were applied on. Frames are stored as the difference to the
+/// previous input, which mostly fits a byte.
+pub fn write_inputs(out: &mut Vec<u8>, inputs: &[(u64, Input)]) {
+    write_varint(out, inputs.len() as u64);
+    let mut last_frame = 0;
+    for &(frame, input) in inputs {
+        write_varint(out, frame - last_frame);
+        out.push(input_to_code(input));
+        last_frame = frame;
+    }
+}
+
+/// Pieces are stored as their index in the piece set of the rules.
+pub fn tetromino_to_code(tetromino: Tetromino) -> u8 {
+    tetromino.index()
+}
+
+pub fn direction_to_code(direction: Direction) -> u8 {
+    match direction {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+fn input_to_code(input: Input) -> u8 {
+    match input {
+        Input::MoveLeft => 0,
+        Input::MoveRight => 1,
+        Input::SoftDrop => 2,
+        Input::RotateCw => 3,
+        Input::RotateCcw => 4,
+        Input::Rotate180 => 5,
+        Input::HardDrop => 6,
+        Input::Hold => 7,
+    }
+}
+
+/// Reads the values written by the functions above, failing on anything out of place.
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Starts reading at the given position, after the header of the format.
+    pub fn new(bytes: &'a [u8], pos: usize) -> Self {
+        Reader { bytes, pos }
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.saturating_add(len);
+        let bytes = self
+            .bytes
+            .get(self.pos..end)
+            .ok_or("File is truncated")?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> Result<bool, String> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(format!("Expected a yes or no, found {}", value)),
+        }
+    }
+
+    pub fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().expect("Took exactly N bytes"))
+    }
+
+    pub fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("File holds a number too large".to_string())
+    }
+
+    /// Reads a number that has to fit the given type.
+    pub fn varint_as<T: TryFrom<u64>>(&mut self) -> Result<T, String> {
+        let value = self.varint()?;
+        T::try_from(value).map_err(|_| format!("Number {} is out of range", value))
+    }
+
+    pub fn str(&mut self) -> Result<&'a str, String> {
+        let len = self.varint_as()?;
+        std::str::from_utf8(self.take(len)?).map_err(|_| "File holds invalid text".to_string())
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8], String> {
+        let len = self.varint_as()?;
+        self.take(len)
+    }
+
+    pub fn rules(&mut self) -> Result<Rules, String> {
+        Ok(Rules {
+            hold_penalty: self.varint()?,
+            randomizer: self.str()?.parse()?,
+            lock_delay_ms: self.varint_as()?,
+            max_lock_resets: self.byte()?,
+            gravity: self.str()?.parse()?,
+            start_level: self.byte()?,
+            lines_per_level: self.varint_as()?,
+            preview_size: self.varint_as()?,
+            field_width: self.varint_as()?,
+            field_height: self.varint_as()?,
+            field_buffer: self.varint_as()?,
+            mode: self.str()?.parse()?,
+            pieces: self.piece_set()?,
+        })
+    }
+
+    fn piece_set(&mut self) -> Result<Arc<PieceSet>, String> {
+        let name = self.str()?;
+        match self.str()? {
+            "" => PieceSet::builtin(name).ok_or(format!("Unknown piece set '{}'", name)),
+            source => PieceSet::parse(name, source).map(Arc::new),
+        }
+    }
+
+    pub fn inputs(&mut self) -> Result<Vec<(u64, Input)>, String> {
+        let count: usize = self.varint_as()?;
+        let mut inputs = Vec::with_capacity(count.min(self.bytes.len()));
+        let mut frame = 0u64;
+        for _ in 0..count {
+            frame = frame
+                .checked_add(self.varint()?)
+                .ok_or("Input frames overflow")?;
+            let code = self.byte()?;
+            let input = Input::ALL
+                .into_iter()
+                .find(|&input| input_to_code(input) == code)
+                .ok_or(format!("Unknown input {}", code))?;
+            inputs.push((frame, input));
+        }
+        Ok(inputs)
+    }
+
+    /// Reads a piece of the given set.
+    pub fn tetromino(&mut self, pieces: &PieceSet) -> Result<Tetromino, String> {
+        let code = self.byte()?;
+        pieces.get(code).ok_or(format!("Unknown tetromino {}", code))
+    }
+
+    pub fn direction(&mut self) -> Result<Direction, String> {
+        let code = self.byte()?;
+        [Direction::Up, Direction::Right, Direction::Down, Direction::Left]
+            .into_iter()
+            .find(|&direction| direction_to_code(direction) == code)
+            .ok_or(format!("Unknown direction {}", code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_round_trip() {
+        let values = [0, 1, 127, 128, 300, 16_384, u32::MAX as u64, u64::MAX];
+        let mut out = Vec::new();
+        for value in values {
+            write_varint(&mut out, value);
+        }
+        assert_eq!(out[..5], [0, 1, 127, 0x80, 1]);
+
+        let mut reader = Reader::new(&out, 0);
+        for value in values {
+            assert_eq!(reader.varint(), Ok(value));
+        }
+        assert!(reader.is_at_end());
+        assert!(reader.varint().is_err());
+    }
+
+    #[test]
+    fn test_varint_as_checks_range() {
+        let mut out = Vec::new();
+        write_varint(&mut out, 300);
+        assert!(Reader::new(&out, 0).varint_as::<u8>().is_err());
+        assert_eq!(Reader::new(&out, 0).varint_as::<u16>(), Ok(300));
+    }
+
+    #[test]
+    fn test_pieces_round_trip() {
+        let mut out = Vec::new();
+        for tetromino in Tetromino::ALL {
+            out.push(tetromino_to_code(tetromino));
+        }
+        out.push(direction_to_code(Direction::Left));
+        out.push(9);
+
+        let mut reader = Reader::new(&out, 0);
+        for tetromino in Tetromino::ALL {
+            assert_eq!(reader.tetromino(&PieceSet::standard()), Ok(tetromino));
+        }
+        assert_eq!(reader.direction(), Ok(Direction::Left));
+        assert!(reader.tetromino(&PieceSet::standard()).is_err());
+    }
+
+    #[test]
+    fn test_custom_piece_set_travels_with_the_rules() {
+        let source = "piece domino red\nXX\n..\n";
+        let rules = Rules {
+            pieces: Arc::new(PieceSet::parse("domino", source).unwrap()),
+            ..Rules::default()
+        };
+        let mut out = Vec::new();
+        write_rules(&mut out, &rules);
+        assert_eq!(Reader::new(&out, 0).rules(), Ok(rules));
+
+        let mut builtin = Vec::new();
+        write_rules(&mut builtin, &Rules::default());
+        assert!(builtin.len() < out.len());
+        assert_eq!(Reader::new(&builtin, 0).rules(), Ok(Rules::default()));
+    }
+}
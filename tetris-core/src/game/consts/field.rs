@@ -0,0 +1,16 @@
+pub const FIELD_WIDTH: u16 = 10;
+pub const FIELD_HEIGHT: u16 = 20;
+/// Hidden rows above the visible field the pieces spawn in.
+pub const FIELD_BUFFER: u16 = 2;
+
+pub const MIN_FIELD_WIDTH: u16 = 4;
+pub const MAX_FIELD_WIDTH: u16 = 40;
+pub const MIN_FIELD_HEIGHT: u16 = 4;
+pub const MAX_FIELD_HEIGHT: u16 = 40;
+pub const MAX_FIELD_BUFFER: u16 = 20;
+
+/// A row of the field, one bit per cell with the leftmost cell in the highest bit of the width.
+/// The bits above the width are always set, so the walls are occupied.
+pub type Row = u64;
+
+pub type Area = Vec<Row>;
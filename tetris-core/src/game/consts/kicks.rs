@@ -0,0 +1,6 @@
+/// Offset (x, y) applied to the rotation box when testing a rotation.
+/// Unlike the usual SRS notation, y grows downwards like the field rows do.
+pub type Kick = (i8, i8);
+
+/// The only test made for rotations without kicks, right in place.
+pub const NO_KICKS: [Kick; 1] = [(0, 0)];
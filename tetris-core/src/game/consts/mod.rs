@@ -1,3 +1,3 @@
 pub mod field;
+pub mod kicks;
 pub mod qube;
-pub mod qubes;
\ No newline at end of file
@@ -0,0 +1,8 @@
+/// One row of a piece, bit 7 being its leftmost cell.
+pub type QubeRow = u8;
+/// Rows and columns a piece can span at most.
+pub const QUBE_SIZE: u16 = QubeRow::BITS as u16;
+
+pub type Qube = [QubeRow; QUBE_SIZE as usize];
+
+pub const EMPTY_QUBE_ROW: QubeRow = 0;
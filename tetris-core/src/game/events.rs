@@ -0,0 +1,61 @@
+use super::consts::kicks::Kick;
+use super::geometry::Direction;
+use super::scoring::{LineClear, TSpin};
+use super::tetromino::Tetromino;
+use std::sync::mpsc::Sender;
+
+/// Something that happened in a game, in the order it happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A new piece entered the field at the top.
+    PieceSpawned {
+        tetromino: Tetromino,
+        direction: Direction,
+    },
+    /// The player moved the current piece by one cell, down being a soft drop.
+    Moved { x: i16, y: i16 },
+    /// The player tried to move the current piece into a wall or the stack.
+    MoveFailed,
+    /// Gravity pulled the current piece down.
+    Fell { rows: u16 },
+    /// The current piece turned, using the given wall kick.
+    Rotated { direction: Direction, kick: Kick },
+    /// None of the wall kicks made room for the rotation.
+    RotateFailed,
+    /// The current piece dropped straight down, right before locking.
+    HardDropped { rows: u16 },
+    /// The given tetromino went into the hold slot.
+    Held { tetromino: Tetromino },
+    /// The hold slot was already used for the current piece.
+    HoldFailed,
+    /// The current piece became part of the stack.
+    Locked { tetromino: Tetromino, t_spin: TSpin },
+    /// The locked piece completed lines, which were removed.
+    LinesCleared { count: u16, kind: LineClear },
+    LevelUp { level: u8 },
+    Paused,
+    Resumed,
+    /// Garbage rows were pushed in under the stack.
+    GarbageAdded { rows: u16 },
+    /// The stack reached the top of the field. `funny` asks for the rare joke effects.
+    GameOver { funny: bool },
+    /// The upper rows of the field were cleared away to make room, in modes that do not top out.
+    StackCleared { rows: u16 },
+    /// The goal of the mode was reached, which ends the game.
+    Cleared,
+    /// The game started over with the given seed.
+    Reset { seed: u64 },
+}
+
+/// Observer of the events of a game, such as audio, statistics or replays.
+pub trait Subscriber: Send {
+    fn notify(&mut self, event: &GameEvent);
+}
+
+/// Channels receive every event, for observers living on another thread.
+/// Events are dropped silently once the receiving end is gone.
+impl Subscriber for Sender<GameEvent> {
+    fn notify(&mut self, event: &GameEvent) {
+        let _ = self.send(*event);
+    }
+}
@@ -0,0 +1,520 @@
+use super::consts::field::{
+    Area, Row, FIELD_BUFFER, FIELD_HEIGHT, FIELD_WIDTH, MAX_FIELD_BUFFER, MAX_FIELD_HEIGHT,
+    MAX_FIELD_WIDTH, MIN_FIELD_HEIGHT, MIN_FIELD_WIDTH,
+};
+use super::consts::qube::{Qube, QubeRow, EMPTY_QUBE_ROW};
+use super::geometry::{Direction, Pos};
+use super::piece_set::Piece;
+use std::cmp;
+use std::fmt::Display;
+use std::ops::Div;
+
+#[derive(Clone)]
+pub struct Field {
+    pub width: u16,
+    /// Visible rows, below the buffer.
+    pub height: u16,
+    /// Hidden rows above the visible ones, where the pieces spawn.
+    pub buffer: u16,
+    /// The buffer rows followed by the visible ones, from the top down.
+    pub area: Area,
+}
+
+impl Field {
+    /// Creates an empty field, panicking if a dimension is outside of the supported range.
+    pub fn new(width: u16, height: u16, buffer: u16) -> Self {
+        assert!((MIN_FIELD_WIDTH..=MAX_FIELD_WIDTH).contains(&width), "Field width out of range");
+        assert!(
+            (MIN_FIELD_HEIGHT..=MAX_FIELD_HEIGHT).contains(&height),
+            "Field height out of range"
+        );
+        assert!(buffer <= MAX_FIELD_BUFFER, "Field buffer out of range");
+
+        Field {
+            width,
+            height,
+            buffer,
+            area: vec![Row::MAX << width; (buffer + height) as usize],
+        }
+    }
+
+    pub fn reset(&mut self) {
+        let empty_row = self.empty_row();
+        self.area.fill(empty_row);
+    }
+
+    /// A row without any filled cells, only the bits outside of the walls set.
+    #[inline(always)]
+    pub fn empty_row(&self) -> Row {
+        Row::MAX << self.width
+    }
+
+    /// All the rows of the field, the buffer included.
+    #[inline(always)]
+    pub fn rows(&self) -> u16 {
+        self.buffer + self.height
+    }
+
+    #[inline(always)]
+    fn get_cube_row_mask(&self, row: &QubeRow, pos_x: u16) -> Row {
+        // FRow: 0b111111_0000000000 -> 1's are the walls, 0's are the area to check
+        // QRow: 0b1110_0000 -> 1's are the qube to check from the highest bit, 0's are ignored
+        // The highest qube bit lands on the cell at pos.x, which is bit width - 1 - pos.x
+        let shift = self.width as i32 - QubeRow::BITS as i32 - pos_x as i32;
+        (if shift >= 0 {
+            (*row as Row) << shift
+        } else {
+            (*row as Row) >> -shift
+        }) | self.empty_row() // OR with the empty row to set out of bounds bits to 1
+    }
+
+    /// Where a new piece enters the field, centered and reaching down into the first visible row.
+    #[inline(always)]
+    pub fn get_start_pos(&self, piece: &Piece, direction: &Direction) -> Option<Pos> {
+        let (width, height) = piece.dimensions(direction);
+        let initial_x = self.width.saturating_sub(width).div(2); // Center the tetromino in the field, considering its width
+        // Pieces taller than the rows below the buffer's last one spawn higher up
+        let initial_y = self.buffer.saturating_sub(1).min(self.rows().saturating_sub(height));
+        let mut pos = Pos::new(initial_x, initial_y).unwrap_or(Pos::zero());
+        let qube = piece.qube(direction);
+
+        if self.can_hold((&qube, width), &pos) {
+            return Some(pos);
+        }
+
+        for i in 2..=self.width {
+            pos.x = if i % 2 == 0 {
+                initial_x + i.div(2)
+            } else {
+                cmp::max(0, initial_x as i32 - i.div(2) as i32) as u16
+            };
+
+            if self.can_hold((&qube, width), &pos) {
+                return Some(pos);
+            }
+        }
+
+        None
+    }
+
+    #[inline(always)]
+    pub fn can_hold(&self, (qube, width): (&Qube, u16), pos: &Pos) -> bool {
+        if u32::from(width) + u32::from(pos.x) > u32::from(self.width) {
+            // If the qube width + position x exceeds the field width, it cannot be held
+            return false;
+        }
+
+        let empty_row = self.empty_row();
+        for (y, row) in qube
+            .iter()
+            .enumerate()
+            .filter(|&(_, &row)| row != EMPTY_QUBE_ROW)
+        {
+            let qube_row_mask = self.get_cube_row_mask(row, pos.x);
+
+            match self.area.get(pos.y as usize + y) {
+                Some(&field_row) => {
+                    if (field_row & qube_row_mask) != empty_row {
+                        // If the field row AND the qube row mask is not empty, it means there is a collision
+                        return false;
+                    }
+                }
+
+                None => {
+                    // If the position is out of bounds, we cannot hold the qube
+                    return false;
+                }
+            }
+        }
+
+        // If we reach here, it means the qube can be held in the field at the given position
+        true
+    }
+
+    /// The rows the qube covers when placed at the position, each with the cells it fills.
+    pub fn covered_rows<'a>(
+        &'a self,
+        qube: &'a Qube,
+        pos: &'a Pos,
+    ) -> impl Iterator<Item = (u16, Row)> + 'a {
+        qube.iter()
+            .enumerate()
+            .filter(|&(_, &row)| row != EMPTY_QUBE_ROW)
+            .map(|(y, row)| (pos.y + y as u16, self.get_cube_row_mask(row, pos.x)))
+    }
+
+    pub fn place(&mut self, qube_with_width: (&Qube, u16), pos: &Pos) -> Result<(), &'static str> {
+        if !self.can_hold(qube_with_width, pos) {
+            return Err("Cannot place qube at the given position");
+        }
+
+        for (y, row) in qube_with_width
+            .0
+            .iter()
+            .enumerate()
+            .filter(|&(_, &row)| row != EMPTY_QUBE_ROW)
+        {
+            let qube_row_mask = self.get_cube_row_mask(row, pos.x);
+            let field_row = self.area.get_mut(pos.y as usize + y).unwrap();
+            *field_row |= qube_row_mask; // Set the bits in the field row to 1 where the qube is placed
+        }
+
+        Ok(())
+    }
+
+    /// Clears all completed lines, moving the rows above them down, and returns how many were cleared.
+    pub fn clear_completed_lines(&mut self) -> u16 {
+        let empty_row = self.empty_row();
+        let height = self.area.len();
+        self.area.retain(|&row| row != Row::MAX);
+
+        let cleared_lines = height - self.area.len();
+        self.area.splice(0..0, std::iter::repeat_n(empty_row, cleared_lines));
+
+        cleared_lines as u16
+    }
+
+    /// Pushes the stack up by the given rows and fills the rows freed at the bottom with garbage,
+    /// full but for a hole in the given column. Returns whether filled cells were pushed out of
+    /// the top of the field, which tops the game out.
+    pub fn insert_garbage(&mut self, rows: u16, hole_column: u16) -> bool {
+        assert!(hole_column < self.width, "Garbage hole is outside of the field");
+
+        let empty_row = self.empty_row();
+        let rows = (rows as usize).min(self.area.len());
+        let overflow = self.area[..rows].iter().any(|&row| row != empty_row);
+        let garbage_row = Row::MAX & !(1 << (self.width - 1 - hole_column));
+
+        self.area.rotate_left(rows);
+        let height = self.area.len();
+        self.area[height - rows..].fill(garbage_row);
+
+        overflow
+    }
+
+    /// Empties the given number of rows at the top of the field, the buffer included,
+    /// leaving the rest as it is.
+    pub fn clear_top_rows(&mut self, rows: u16) {
+        let empty_row = self.empty_row();
+        let rows = (rows as usize).min(self.area.len());
+        self.area[..rows].fill(empty_row);
+    }
+
+    /// Whether the cell is filled or outside the walls and floor. Cells above the field are free.
+    pub fn is_occupied(&self, x: i16, y: i16) -> bool {
+        if x < 0 || x >= self.width as i16 || y >= self.area.len() as i16 {
+            return true;
+        }
+
+        y >= 0 && self.area[y as usize] & (1 << (self.width as i16 - 1 - x)) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let empty_row = self.empty_row();
+        self.area.iter().all(|&row| row == empty_row)
+    }
+}
+
+impl Default for Field {
+    fn default() -> Self {
+        Self::new(FIELD_WIDTH, FIELD_HEIGHT, FIELD_BUFFER)
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.area.iter() {
+            writeln!(
+                f,
+                "{:0>width$b}",
+                *row & !self.empty_row(),
+                width = self.width as usize
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Tests generated by Junie AI
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::piece_set::PieceSet;
+    use crate::game::geometry::{Direction, Pos};
+    use crate::game::tetromino::Tetromino;
+
+    /// A standard field without a buffer, so the rows are numbered from the top of the field.
+    fn unbuffered() -> Field {
+        Field::new(FIELD_WIDTH, FIELD_HEIGHT, 0)
+    }
+
+    #[test]
+    fn test_new_field() {
+        let field = Field::default();
+
+        assert_eq!(field.width, FIELD_WIDTH);
+        assert_eq!(field.height, FIELD_HEIGHT);
+        assert_eq!(field.buffer, FIELD_BUFFER);
+        assert_eq!(field.area.len(), (FIELD_BUFFER + FIELD_HEIGHT) as usize);
+        assert!(field.is_empty());
+    }
+
+    #[test]
+    fn test_new_field_of_any_size() {
+        for (width, height) in [(4, 20), (20, 20), (10, 40), (MAX_FIELD_WIDTH, MAX_FIELD_HEIGHT)] {
+            let field = Field::new(width, height, 2);
+            assert_eq!(field.rows(), height + 2);
+            assert!(field.is_empty());
+            assert!(field.is_occupied(width as i16, 0));
+            assert!(!field.is_occupied(width as i16 - 1, 0));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "width")]
+    fn test_new_field_rejects_wide_fields() {
+        Field::new(MAX_FIELD_WIDTH + 1, FIELD_HEIGHT, FIELD_BUFFER);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut field = Field::default();
+
+        // Modify the field
+        field.area[0] = 0xFFFF;
+
+        // Reset the field
+        field.reset();
+
+        // Check that the field is empty
+        assert!(field.is_empty());
+    }
+
+    #[test]
+    fn test_get_start_pos() {
+        let pieces = PieceSet::standard();
+        let field = unbuffered();
+        let tetromino = Tetromino::L;
+        let direction = Direction::Up;
+
+        let pos = field.get_start_pos(&pieces[tetromino], &direction);
+
+        // The start position should be valid
+        assert!(pos.is_some());
+
+        // The start position should be centered horizontally
+        let pos = pos.unwrap();
+        let (width, _) = pieces[tetromino].dimensions(&direction);
+        assert_eq!(pos.x, (field.width - width) / 2);
+        assert_eq!(pos.y, 0);
+    }
+
+    #[test]
+    fn test_start_pos_reaches_into_the_field() {
+        let pieces = PieceSet::standard();
+        for buffer in [1, 2, 4] {
+            let field = Field::new(FIELD_WIDTH, FIELD_HEIGHT, buffer);
+            let pos = field.get_start_pos(&pieces[Tetromino::T], &Direction::Up).unwrap();
+
+            // The lower row of the piece is the first visible row
+            assert_eq!(pos.y + 1, buffer);
+        }
+    }
+
+    #[test]
+    fn test_can_hold() {
+        let pieces = PieceSet::standard();
+        let field = unbuffered();
+        let tetromino = Tetromino::L;
+        let direction = Direction::Up;
+        let qube = pieces[tetromino].qube(&direction);
+        let width = pieces[tetromino].dimensions(&direction).0;
+
+        // Position at the top center of the field
+        let pos = Pos::new((field.width - width) / 2, 0).unwrap();
+
+        // The field should be able to hold the tetromino at the start position
+        assert!(field.can_hold((&qube, width), &pos));
+
+        // Position outside the field horizontally
+        let pos = Pos::new(field.width - width + 1, 0).unwrap();
+
+        // The field should not be able to hold the tetromino outside the field
+        assert!(!field.can_hold((&qube, width), &pos));
+
+        // Test with a position that would place part of the tetromino outside the field vertically
+        // We need a valid position that's close to the bottom of the field
+        let pos = Pos::new(0, field.height - 1).unwrap();
+
+        // The field should not be able to hold the tetromino if it would extend beyond the field
+        // This is because the tetromino's height is greater than 1, so it would extend beyond the field
+        assert!(!field.can_hold((&qube, width), &pos));
+
+        // Positions far outside the field, as a corrupt save could hold, do not overflow
+        let mut pos = Pos::zero();
+        pos.x = u16::MAX;
+        assert!(!field.can_hold((&qube, width), &pos));
+    }
+
+    #[test]
+    fn test_place_on_narrow_and_wide_fields() {
+        let pieces = PieceSet::standard();
+        let qube = pieces[Tetromino::I].qube(&Direction::Up);
+        let (width, _) = pieces[Tetromino::I].dimensions(&Direction::Up);
+
+        // An I fills a whole row of a 4 wide field
+        let mut narrow = Field::new(4, FIELD_HEIGHT, 0);
+        narrow.place((&qube, width), &Pos::new(0, FIELD_HEIGHT - 1).unwrap()).unwrap();
+        assert_eq!(narrow.clear_completed_lines(), 1);
+        assert!(narrow.is_empty());
+
+        let mut wide = Field::new(MAX_FIELD_WIDTH, FIELD_HEIGHT, 0);
+        let pos = Pos::new(MAX_FIELD_WIDTH - width, 3).unwrap();
+        wide.place((&qube, width), &pos).unwrap();
+        assert!((MAX_FIELD_WIDTH - width..MAX_FIELD_WIDTH).all(|x| wide.is_occupied(x as i16, 3)));
+        assert!(!wide.is_occupied((MAX_FIELD_WIDTH - width - 1) as i16, 3));
+        assert!(!wide.can_hold((&qube, width), &Pos::new(MAX_FIELD_WIDTH - width + 1, 3).unwrap()));
+    }
+
+    #[test]
+    fn test_place() {
+        let pieces = PieceSet::standard();
+        let mut field = unbuffered();
+        let tetromino = Tetromino::L;
+        let direction = Direction::Up;
+        let qube = pieces[tetromino].qube(&direction);
+        let width = pieces[tetromino].dimensions(&direction).0;
+
+        // Position at the top center of the field
+        let pos = Pos::new((field.width - width) / 2, 0).unwrap();
+
+        // Place the tetromino
+        let result = field.place((&qube, width), &pos);
+
+        // The placement should succeed
+        assert!(result.is_ok());
+
+        // The field should no longer be empty
+        assert!(!field.is_empty());
+
+        // Try to place the tetromino at the same position again
+        let result = field.place((&qube, width), &pos);
+
+        // The placement should fail because the position is already occupied
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_clear_completed_lines() {
+        let mut field = unbuffered();
+        let empty_row = field.empty_row();
+
+        // No completed lines initially
+        assert_eq!(field.clear_completed_lines(), 0);
+
+        // Fill a line
+        field.area[5] = Row::MAX;
+
+        // Clear the completed line
+        assert_eq!(field.clear_completed_lines(), 1);
+
+        // The field should be empty again
+        assert_eq!(field.area[5], empty_row);
+
+        // Fill multiple lines
+        field.area[5] = Row::MAX;
+        field.area[6] = Row::MAX;
+        field.area[7] = Row::MAX;
+
+        // Clear the completed lines
+        assert_eq!(field.clear_completed_lines(), 3);
+
+        // The field should be empty again
+        assert_eq!(field.area[5], empty_row);
+        assert_eq!(field.area[6], empty_row);
+        assert_eq!(field.area[7], empty_row);
+        assert_eq!(field.area.len(), FIELD_HEIGHT as usize);
+    }
+
+    #[test]
+    fn test_clear_separated_lines() {
+        let mut field = unbuffered();
+        let partial_row = field.empty_row() | 0b1;
+
+        field.area[16] = partial_row;
+        field.area[17] = Row::MAX;
+        field.area[18] = partial_row;
+        field.area[19] = Row::MAX;
+
+        assert_eq!(field.clear_completed_lines(), 2);
+
+        // The partial rows fall down and keep their order
+        assert_eq!(field.area[19], partial_row);
+        assert_eq!(field.area[18], partial_row);
+        assert_eq!(field.area[17], field.empty_row());
+    }
+
+    #[test]
+    fn test_insert_garbage() {
+        let mut field = unbuffered();
+        let partial_row = field.empty_row() | 0b1;
+        field.area[19] = partial_row;
+
+        assert!(!field.insert_garbage(2, 3));
+
+        // The stack moves up and the garbage keeps its hole open
+        assert_eq!(field.area[17], partial_row);
+        for y in [18, 19] {
+            assert!(!field.is_occupied(3, y));
+            assert!((0..10).filter(|&x| x != 3).all(|x| field.is_occupied(x, y)));
+        }
+        assert_eq!(field.clear_completed_lines(), 0);
+    }
+
+    #[test]
+    fn test_insert_garbage_tops_out() {
+        let mut field = unbuffered();
+        field.area[1] = field.empty_row() | 0b1;
+
+        assert!(!field.insert_garbage(1, 0));
+        assert_eq!(field.area[0], field.empty_row() | 0b1);
+        assert!(field.insert_garbage(1, 0));
+    }
+
+    #[test]
+    fn test_clear_top_rows() {
+        let mut field = unbuffered();
+        field.area.fill(Row::MAX - 1);
+
+        field.clear_top_rows(10);
+        assert!(field.area[..10].iter().all(|&row| row == field.empty_row()));
+        assert!(field.area[10..].iter().all(|&row| row == Row::MAX - 1));
+    }
+
+    #[test]
+    fn test_is_occupied() {
+        let mut field = unbuffered();
+        field.area[19] = field.empty_row() | 0b10_0000_0001;
+
+        assert!(field.is_occupied(0, 19));
+        assert!(field.is_occupied(9, 19));
+        assert!(!field.is_occupied(1, 19));
+
+        // Walls and floor are occupied, the space above the field is not
+        assert!(field.is_occupied(-1, 5));
+        assert!(field.is_occupied(10, 5));
+        assert!(field.is_occupied(5, 20));
+        assert!(!field.is_occupied(5, -1));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut field = Field::default();
+        assert!(field.is_empty());
+
+        let bottom = field.area.len() - 1;
+        field.area[bottom] = field.empty_row() | 0b1;
+        assert!(!field.is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+//! Finesse: putting every piece into place with as few moves and rotations as possible.
+//! Holding left or right to shift a piece counts as one input per cell it moved,
+//! since the game only sees the moves, not whether a key was tapped or held.
+
+use super::consts::field::Row;
+use super::consts::qube::QUBE_SIZE;
+use super::field::Field;
+use super::geometry::{Direction, Pos};
+use super::object::Object;
+use super::piece_set::PieceSet;
+use super::tetromino::Tetromino;
+use std::collections::VecDeque;
+
+/// Searches no deeper than this, no placement on an open field takes more.
+const MAX_INPUTS: u32 = 12;
+
+/// Fewest moves and rotations taking a piece from where it spawned to a spot it drops straight
+/// into the same cells as the given placement from. None if no such spot can be reached from the
+/// spawn row, such as for placements tucked under an overhang.
+pub fn fewest_inputs(
+    field: &Field,
+    pieces: &PieceSet,
+    tetromino: Tetromino,
+    spawn: (Direction, Pos),
+    placement: (Direction, Pos),
+) -> Option<u32> {
+    let placed = Object::new(pieces, tetromino, placement.0, placement.1);
+    let target = cells_of(field, &dropped(field, pieces, &placed));
+    let mut visited = vec![(spawn.0, spawn.1.x, spawn.1.y)];
+    let mut queue = VecDeque::from([(Object::new(pieces, tetromino, spawn.0, spawn.1), 0)]);
+
+    while let Some((object, inputs)) = queue.pop_front() {
+        if cells_of(field, &dropped(field, pieces, &object)) == target {
+            return Some(inputs);
+        }
+        if inputs == MAX_INPUTS {
+            continue;
+        }
+
+        for next in neighbours(field, pieces, &object) {
+            let state = (next.direction, next.pos.x, next.pos.y);
+            if !visited.contains(&state) {
+                visited.push(state);
+                queue.push_back((next, inputs + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Every object a single move or rotation turns the given one into.
+fn neighbours(field: &Field, pieces: &PieceSet, object: &Object) -> Vec<Object> {
+    let mut objects = Vec::with_capacity(5);
+
+    for x in [-1, 1] {
+        if let Some(pos) = object.pos.copy_offset(x, 0)
+            && field.can_hold(object.get_qube_with_width(), &pos)
+        {
+            objects.push(Object::new(pieces, object.tetromino, object.direction, pos));
+        }
+    }
+
+    let directions = [
+        object.direction.copy_rotate(),
+        object.direction.copy_rotate_ccw(),
+        object.direction.copy_rotate_180(),
+    ];
+    for direction in directions {
+        let piece = &pieces[object.tetromino];
+        let qube = piece.qube(&direction);
+        let width = piece.dimensions(&direction).0;
+        let kicked_pos = object
+            .get_rotation_positions(pieces, &direction)
+            .into_iter()
+            .find(|(_, pos)| field.can_hold((&qube, width), pos));
+        if let Some((_, pos)) = kicked_pos {
+            objects.push(Object::new(pieces, object.tetromino, direction, pos));
+        }
+    }
+
+    objects
+}
+
+/// The given object moved down as far as it goes.
+fn dropped(field: &Field, pieces: &PieceSet, object: &Object) -> Object {
+    let mut pos = object.pos;
+    while field.can_hold(object.get_qube_with_width(), &pos.copy_mod_y(1)) {
+        pos.mod_y(1);
+    }
+    Object::new(pieces, object.tetromino, object.direction, pos)
+}
+
+/// Cells the object covers, as the rows it reaches into with the cells it fills in each.
+fn cells_of(field: &Field, object: &Object) -> [(u16, Row); QUBE_SIZE as usize] {
+    let mut cells = [(0, 0); QUBE_SIZE as usize];
+    for (cell, row) in cells.iter_mut().zip(field.covered_rows(&object.qube, &object.pos)) {
+        *cell = row;
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::piece_set::PieceSet;
+
+    fn spawn(field: &Field, tetromino: Tetromino, direction: Direction) -> (Direction, Pos) {
+        let piece = &PieceSet::standard()[tetromino];
+        (direction, field.get_start_pos(piece, &direction).unwrap())
+    }
+
+    #[test]
+    fn test_dropping_in_place_takes_no_inputs() {
+        let pieces = PieceSet::standard();
+        let field = Field::default();
+        let spawn = spawn(&field, Tetromino::T, Direction::Up);
+
+        assert_eq!(fewest_inputs(&field, &pieces, Tetromino::T, spawn, spawn), Some(0));
+    }
+
+    #[test]
+    fn test_moves_and_rotations_are_counted() {
+        let pieces = PieceSet::standard();
+        let field = Field::default();
+        let spawn = spawn(&field, Tetromino::L, Direction::Up);
+        let placement = (Direction::Up, Pos::new(0, 20).unwrap());
+        let steps = spawn.1.x as u32;
+
+        assert_eq!(fewest_inputs(&field, &pieces, Tetromino::L, spawn, placement), Some(steps));
+
+        // Upside down takes a single 180 rotation on top of the moves
+        let mut object = Object::new(&pieces, Tetromino::L, spawn.0, spawn.1);
+        object.rotate_180(&pieces);
+        let placement = (object.direction, object.pos);
+        assert_eq!(fewest_inputs(&field, &pieces, Tetromino::L, spawn, placement), Some(1));
+    }
+
+    #[test]
+    fn test_symmetric_placements_are_the_same() {
+        let pieces = PieceSet::standard();
+        let field = Field::default();
+        let spawn = spawn(&field, Tetromino::O, Direction::Up);
+        let mut object = Object::new(&pieces, Tetromino::O, spawn.0, spawn.1);
+        object.rotate(&pieces);
+
+        // An O looks the same in every direction, so rotating it only wastes an input
+        let placement = (object.direction, object.pos);
+        assert_eq!(fewest_inputs(&field, &pieces, Tetromino::O, spawn, placement), Some(0));
+    }
+
+    #[test]
+    fn test_tucks_are_not_judged() {
+        let pieces = PieceSet::standard();
+        let mut field = Field::default();
+        // A roof over the left half of the two bottom rows
+        field.area[19] |= 0b11111_00000;
+        let spawn = spawn(&field, Tetromino::I, Direction::Up);
+        let placement = (Direction::Up, Pos::new(0, 20).unwrap());
+
+        assert_eq!(fewest_inputs(&field, &pieces, Tetromino::I, spawn, placement), None);
+    }
+}
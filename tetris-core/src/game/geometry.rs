@@ -1,4 +1,4 @@
-use super::consts::field::{FIELD_HEIGHT, FIELD_WIDTH};
+use super::consts::field::{MAX_FIELD_BUFFER, MAX_FIELD_HEIGHT, MAX_FIELD_WIDTH};
 use rand::Rng;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,8 +10,7 @@ pub enum Direction {
 }
 
 impl Direction {
-    pub fn random() -> Self {
-        let mut rng = rand::rng();
+    pub fn random(rng: &mut impl Rng) -> Self {
         match rng.random_range(0..4) {
             0 => Direction::Up,
             1 => Direction::Down,
@@ -29,10 +28,38 @@ impl Direction {
         }
     }
 
+    pub fn copy_rotate_ccw(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn copy_rotate_180(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
     pub fn rotate(&mut self) -> &Self {
         *self = self.copy_rotate();
         self
     }
+
+    pub fn rotate_ccw(&mut self) -> &Self {
+        *self = self.copy_rotate_ccw();
+        self
+    }
+
+    pub fn rotate_180(&mut self) -> &Self {
+        *self = self.copy_rotate_180();
+        self
+    }
 }
 
 // Tests generated by Junie AI
@@ -63,6 +90,33 @@ mod direction_tests {
         dir.rotate();
         assert_eq!(dir, Direction::Up);
     }
+
+    #[test]
+    fn test_copy_rotate_ccw() {
+        assert_eq!(Direction::Up.copy_rotate_ccw(), Direction::Left);
+        assert_eq!(Direction::Left.copy_rotate_ccw(), Direction::Down);
+        assert_eq!(Direction::Down.copy_rotate_ccw(), Direction::Right);
+        assert_eq!(Direction::Right.copy_rotate_ccw(), Direction::Up);
+    }
+
+    #[test]
+    fn test_copy_rotate_180() {
+        let all = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+        for dir in all {
+            assert_eq!(dir.copy_rotate_180(), dir.copy_rotate().copy_rotate());
+            assert_eq!(dir.copy_rotate().copy_rotate_ccw(), dir);
+        }
+    }
+
+    #[test]
+    fn test_rotate_ccw_and_180() {
+        let mut dir = Direction::Up;
+        dir.rotate_ccw();
+        assert_eq!(dir, Direction::Left);
+
+        dir.rotate_180();
+        assert_eq!(dir, Direction::Right);
+    }
 }
 
 #[derive(Debug)]
@@ -77,8 +131,9 @@ pub struct Pos {
 }
 
 impl Pos {
+    /// Creates a position inside of the largest field there can be.
     pub fn new(x: u16, y: u16) -> Result<Self, PosError> {
-        if x >= FIELD_WIDTH || y >= FIELD_HEIGHT {
+        if x >= MAX_FIELD_WIDTH || y >= MAX_FIELD_BUFFER + MAX_FIELD_HEIGHT {
             return Err(PosError::OutOfBounds);
         }
 
@@ -121,6 +176,13 @@ impl Pos {
         self.y += inc;
         self
     }
+
+    /// Returns the position moved by the given offset, or `None` if it would leave the field on the left or top.
+    pub fn copy_offset(&self, x: i16, y: i16) -> Option<Self> {
+        let new_x = u16::try_from(self.x as i16 + x).ok()?;
+        let new_y = u16::try_from(self.y as i16 + y).ok()?;
+        Some(Pos { x: new_x, y: new_y })
+    }
 }
 
 // Tests generated by Junie AI
@@ -134,20 +196,22 @@ mod pos_tests {
         assert_eq!(pos.x, 0);
         assert_eq!(pos.y, 0);
 
-        let pos = Pos::new(FIELD_WIDTH - 1, FIELD_HEIGHT - 1).unwrap();
-        assert_eq!(pos.x, FIELD_WIDTH - 1);
-        assert_eq!(pos.y, FIELD_HEIGHT - 1);
+        let bottom = MAX_FIELD_BUFFER + MAX_FIELD_HEIGHT - 1;
+        let pos = Pos::new(MAX_FIELD_WIDTH - 1, bottom).unwrap();
+        assert_eq!(pos.x, MAX_FIELD_WIDTH - 1);
+        assert_eq!(pos.y, bottom);
     }
 
     #[test]
     fn test_new_invalid_position() {
-        let pos = Pos::new(FIELD_WIDTH, 0);
+        let rows = MAX_FIELD_BUFFER + MAX_FIELD_HEIGHT;
+        let pos = Pos::new(MAX_FIELD_WIDTH, 0);
         assert!(pos.is_err());
 
-        let pos = Pos::new(0, FIELD_HEIGHT);
+        let pos = Pos::new(0, rows);
         assert!(pos.is_err());
 
-        let pos = Pos::new(FIELD_WIDTH, FIELD_HEIGHT);
+        let pos = Pos::new(MAX_FIELD_WIDTH, rows);
         assert!(pos.is_err());
     }
 
@@ -212,4 +276,17 @@ mod pos_tests {
         assert_eq!(pos.x, 5);
         assert_eq!(pos.y, 8);
     }
+
+    #[test]
+    fn test_copy_offset() {
+        let pos = Pos::new(5, 5).unwrap();
+
+        let new_pos = pos.copy_offset(-2, 3).unwrap();
+        assert_eq!(new_pos.x, 3);
+        assert_eq!(new_pos.y, 8);
+
+        // Offsets leaving the field to the left or top are rejected
+        assert!(pos.copy_offset(-6, 0).is_none());
+        assert!(pos.copy_offset(0, -6).is_none());
+    }
 }
@@ -0,0 +1,167 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Speed at which the current object falls, depending on the level.
+pub trait GravityCurve: Send + Sync {
+    /// Milliseconds it takes to fall one row. Zero drops straight onto the stack,
+    /// infinity leaves the object where it is.
+    fn row_interval_ms(&self, level: u8) -> f64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GravityKind {
+    /// `(0.8 - (level - 1) * 0.007) ^ (level - 1)` seconds per row, as in modern Tetris games.
+    Guideline,
+    /// Frames per row taken from the NES version, running at 60 frames per second.
+    Nes,
+    /// Every object drops onto the stack the moment it spawns, on any level.
+    Instant,
+    /// Objects only move down when dropped by the player.
+    Off,
+}
+
+impl GravityKind {
+    pub const ALL: [GravityKind; 4] =
+        [GravityKind::Guideline, GravityKind::Nes, GravityKind::Instant, GravityKind::Off];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GravityKind::Guideline => "guideline",
+            GravityKind::Nes => "nes",
+            GravityKind::Instant => "20g",
+            GravityKind::Off => "off",
+        }
+    }
+
+    pub fn create(&self) -> Box<dyn GravityCurve> {
+        match self {
+            GravityKind::Guideline => Box::new(GuidelineGravity),
+            GravityKind::Nes => Box::new(NesGravity),
+            GravityKind::Instant => Box::new(InstantGravity),
+            GravityKind::Off => Box::new(NoGravity),
+        }
+    }
+}
+
+impl Display for GravityKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for GravityKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GravityKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = GravityKind::ALL.iter().map(|kind| kind.name()).collect();
+                format!("Unknown gravity '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+pub struct GuidelineGravity;
+
+impl GravityCurve for GuidelineGravity {
+    fn row_interval_ms(&self, level: u8) -> f64 {
+        let level = level.max(1) as f64;
+        let base = 0.8 - (level - 1.0) * 0.007;
+        if base <= 0.0 {
+            return 0.0;
+        }
+
+        base.powf(level - 1.0) * 1000.0
+    }
+}
+
+const NES_FRAME_MS: f64 = 1000.0 / 60.0988;
+/// Frames per row on levels 0 to 18. Levels up to 28 take 2 frames, every level after that 1.
+const NES_FRAMES_PER_ROW: [u8; 19] =
+    [48, 43, 38, 33, 28, 23, 18, 13, 8, 6, 5, 5, 5, 4, 4, 4, 3, 3, 3];
+
+pub struct NesGravity;
+
+impl GravityCurve for NesGravity {
+    fn row_interval_ms(&self, level: u8) -> f64 {
+        let frames = match NES_FRAMES_PER_ROW.get(level as usize) {
+            Some(&frames) => frames,
+            None if level < 29 => 2,
+            None => 1,
+        };
+
+        frames as f64 * NES_FRAME_MS
+    }
+}
+
+pub struct InstantGravity;
+
+impl GravityCurve for InstantGravity {
+    fn row_interval_ms(&self, _level: u8) -> f64 {
+        0.0
+    }
+}
+
+pub struct NoGravity;
+
+impl GravityCurve for NoGravity {
+    fn row_interval_ms(&self, _level: u8) -> f64 {
+        f64::INFINITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kind() {
+        for kind in GravityKind::ALL {
+            assert_eq!(kind.name().parse::<GravityKind>(), Ok(kind));
+        }
+
+        assert!("moon".parse::<GravityKind>().is_err());
+    }
+
+    #[test]
+    fn test_guideline_gravity() {
+        let gravity = GuidelineGravity;
+
+        assert_eq!(gravity.row_interval_ms(1), 1000.0);
+        assert!((gravity.row_interval_ms(2) - 793.0).abs() < 0.5);
+        assert!((gravity.row_interval_ms(15) - 7.05).abs() < 0.05);
+
+        // Level 0 is treated like level 1 instead of speeding things up
+        assert_eq!(gravity.row_interval_ms(0), 1000.0);
+
+        // Gravity only gets faster, until it reaches 20G
+        for level in 1..u8::MAX {
+            assert!(gravity.row_interval_ms(level + 1) <= gravity.row_interval_ms(level));
+        }
+        assert_eq!(gravity.row_interval_ms(u8::MAX), 0.0);
+    }
+
+    #[test]
+    fn test_nes_gravity() {
+        let gravity = NesGravity;
+
+        assert!((gravity.row_interval_ms(0) - 48.0 * NES_FRAME_MS).abs() < f64::EPSILON);
+        assert!((gravity.row_interval_ms(18) - 3.0 * NES_FRAME_MS).abs() < f64::EPSILON);
+        assert!((gravity.row_interval_ms(19) - 2.0 * NES_FRAME_MS).abs() < f64::EPSILON);
+        assert!((gravity.row_interval_ms(28) - 2.0 * NES_FRAME_MS).abs() < f64::EPSILON);
+        assert!((gravity.row_interval_ms(29) - NES_FRAME_MS).abs() < f64::EPSILON);
+        assert!((gravity.row_interval_ms(u8::MAX) - NES_FRAME_MS).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_instant_gravity() {
+        assert_eq!(InstantGravity.row_interval_ms(1), 0.0);
+    }
+
+    #[test]
+    fn test_no_gravity() {
+        assert_eq!(NoGravity.row_interval_ms(1), f64::INFINITY);
+    }
+}
@@ -0,0 +1,26 @@
+/// Something the player does to the current piece.
+/// Inputs applied through `Game::apply` are recorded with their frame, so the game can be replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    RotateCw,
+    RotateCcw,
+    Rotate180,
+    HardDrop,
+    Hold,
+}
+
+impl Input {
+    pub const ALL: [Input; 8] = [
+        Input::MoveLeft,
+        Input::MoveRight,
+        Input::SoftDrop,
+        Input::RotateCw,
+        Input::RotateCcw,
+        Input::Rotate180,
+        Input::HardDrop,
+        Input::Hold,
+    ];
+}
@@ -0,0 +1,1593 @@
+use crate::game::clock::{Clock, WallClock};
+use crate::game::consts::field::{
+    Row, MAX_FIELD_BUFFER, MAX_FIELD_HEIGHT, MAX_FIELD_WIDTH, MIN_FIELD_HEIGHT, MIN_FIELD_WIDTH,
+};
+use crate::game::consts::kicks::Kick;
+use crate::game::events::{GameEvent, Subscriber};
+use crate::game::field::Field;
+use crate::game::geometry::{Direction, Pos};
+use crate::game::gravity::GravityCurve;
+use crate::game::input::Input;
+use crate::game::mode::GameMode;
+use crate::game::object::Object;
+use crate::game::rendering::Renderer;
+use crate::game::replay::{Replay, ReplayResult};
+use crate::game::rules::{Rules, MAX_PREVIEW_SIZE};
+use crate::game::scoring::{LineClear, Scoring, TSpin, HARD_DROP_POINTS, SOFT_DROP_POINTS};
+use crate::game::randomizer::Randomizer;
+use crate::game::tetromino::Tetromino;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+pub mod bot;
+pub mod clock;
+pub mod codec;
+pub mod consts;
+pub mod events;
+pub mod field;
+pub mod finesse;
+pub mod geometry;
+pub mod gravity;
+pub mod input;
+pub mod mode;
+pub mod object;
+pub mod piece_set;
+pub mod randomizer;
+pub mod rendering;
+pub mod replay;
+pub mod rules;
+pub mod save;
+pub mod scoring;
+pub mod tetromino;
+
+/// Random number generator behind everything random in a game.
+/// ChaCha8 yields the same numbers on every platform, so a seed always deals the same pieces.
+pub type GameRng = ChaCha8Rng;
+
+/// Frames the game advances per second of play.
+pub const FRAMES_PER_SECOND: u64 = 60;
+/// Length of the fixed step `Game::update` advances the game by.
+pub const FRAME_DURATION: Duration = Duration::from_nanos(1_000_000_000 / FRAMES_PER_SECOND);
+
+/// How long the last line clear stays on screen.
+const LINE_CLEAR_DISPLAY_MS: i64 = 1500;
+
+/// How long clearing away the top of the stack is animated for, in modes that do not top out.
+const STACK_CLEAR_ANIMATION_MS: i64 = 600;
+
+/// Chance of the game over asking for the joke effects instead of the regular ones.
+const FUNNY_GAME_OVER_CHANCE: f64 = 0.01;
+
+pub struct Game {
+    pub field: Field,
+    seed: u64,
+    rng: GameRng,
+    randomizer: Box<dyn Randomizer>,
+    gravity: Box<dyn GravityCurve>,
+    mode: Box<dyn GameMode>,
+    pub current_object: Object,
+    /// Upcoming pieces, the first one spawning next
+    pub next_objects: VecDeque<(Tetromino, Direction)>,
+    pub held_object: Option<Tetromino>,
+    hold_used: bool,
+    /// Frames played so far, which all game time is counted in
+    frame: u64,
+    /// Time passed to `update` that did not add up to a whole frame yet
+    frame_remainder: Duration,
+    clock: Box<dyn Clock>,
+    /// Clock time of the last `tick`
+    last_tick_at: Option<Duration>,
+    /// Time the current object landed, while it is waiting to lock
+    lock_started_at: Option<i64>,
+    lock_resets: u8,
+    /// Lowest row the current object reached so far
+    lowest_row: u16,
+    /// Direction and position the current object spawned in, to judge its finesse by
+    spawned_at: (Direction, Pos),
+    /// Moves and rotations applied to the current object
+    piece_inputs: u32,
+    /// Kick used by the last rotation, as long as nothing but rotations happened since
+    last_rotation_kick: Option<Kick>,
+    scoring: Scoring,
+    /// Last line clear or T-spin worth showing, until its display time ran out
+    pub last_clear: Option<LineClear>,
+    last_clear_at: i64,
+    /// Rows at the top of the stack last cleared away instead of topping out, while animated
+    last_stack_clear: Option<u16>,
+    last_stack_clear_at: i64,
+    pub rules: Rules,
+    pub level: u8,
+    /// Lines cleared so far, which decide the level
+    pub lines: u32,
+    pub score: u64,
+    /// Garbage rows at the bottom of the field, below everything the player stacked
+    garbage_rows: u16,
+    /// Garbage rows cleared so far
+    garbage_cleared: u32,
+    /// Pieces locked so far
+    pieces: u32,
+    /// Pieces put into place with more moves and rotations than needed
+    finesse_faults: u32,
+    paused: bool,
+    pub game_over: bool,
+    /// Whether the game ended by reaching the goal of its mode rather than by topping out
+    cleared: bool,
+    /// Time gravity last moved the current object, or would have if it was not resting
+    last_fall_at: i64,
+    renderer: Box<dyn Renderer>,
+    subscribers: Vec<Box<dyn Subscriber>>,
+    should_exit: bool,
+    /// Inputs applied since the game started, with the frame each was applied on
+    inputs: Vec<(u64, Input)>,
+}
+
+impl Game {
+    pub fn new(
+        renderer: Box<dyn Renderer>,
+        rules: Rules,
+        seed: u64,
+    ) -> Self {
+        // The field has to be wide and high enough for every piece of the set
+        let max_size = rules.pieces.get_max_size();
+        let buffer = rules.field_buffer.min(MAX_FIELD_BUFFER);
+        let min_height = MIN_FIELD_HEIGHT.max(max_size.saturating_sub(buffer));
+        let field = Field::new(
+            rules.field_width.clamp(MIN_FIELD_WIDTH.max(max_size), MAX_FIELD_WIDTH),
+            rules.field_height.clamp(min_height, MAX_FIELD_HEIGHT),
+            buffer,
+        );
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut randomizer = rules.randomizer.create(&rules.pieces);
+        let gravity = rules.gravity.create();
+        let mode = rules.mode.create();
+        let tetromino = randomizer.next_tetromino(&mut rng);
+
+        let direction = Direction::random(&mut rng);
+        // The field fits every piece, so an empty one always has room for the first
+        let pos = field.get_start_pos(&rules.pieces[tetromino], &direction).unwrap_or(Pos::zero());
+
+        let mut game = Game {
+            field,
+            current_object: Object::new(&rules.pieces, tetromino, direction, pos),
+            next_objects: VecDeque::with_capacity(MAX_PREVIEW_SIZE),
+            seed,
+            rng,
+            randomizer,
+            gravity,
+            mode,
+            held_object: None,
+            hold_used: false,
+            lock_started_at: None,
+            lock_resets: 0,
+            lowest_row: pos.y,
+            spawned_at: (direction, pos),
+            piece_inputs: 0,
+            last_rotation_kick: None,
+            scoring: Scoring::new(),
+            last_clear: None,
+            last_clear_at: 0,
+            last_stack_clear: None,
+            last_stack_clear_at: 0,
+            level: rules.start_level,
+            lines: 0,
+            rules,
+            score: 0,
+            garbage_rows: 0,
+            garbage_cleared: 0,
+            pieces: 0,
+            finesse_faults: 0,
+            paused: true,
+            game_over: false,
+            cleared: false,
+            should_exit: false,
+            renderer,
+            subscribers: Vec::new(),
+            frame: 0,
+            frame_remainder: Duration::ZERO,
+            clock: Box::new(WallClock::new()),
+            last_tick_at: None,
+            last_fall_at: 0,
+            inputs: Vec::new(),
+        };
+        game.fill_next_objects();
+        game.add_garbage();
+        game.renderer.prerender(&game);
+        game.renderer.render(&game);
+        game
+    }
+
+    /// Seed the current game was started with. The same seed always deals the same pieces.
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restarts the game with a new seed, derived from the current one so whole sessions replay.
+    pub fn reset(&mut self) {
+        let seed = self.rng.random();
+        self.reset_with_seed(seed);
+    }
+
+    pub fn reset_with_seed(&mut self, seed: u64) {
+        self.field.reset();
+
+        self.seed = seed;
+        self.rng = GameRng::seed_from_u64(seed);
+        self.randomizer.reset();
+        let tetromino = self.randomizer.next_tetromino(&mut self.rng);
+
+        let direction = Direction::random(&mut self.rng);
+        let piece = &self.rules.pieces[tetromino];
+        let pos = self.field.get_start_pos(piece, &direction).unwrap_or(Pos::zero());
+
+        self.current_object = Object::new(&self.rules.pieces, tetromino, direction, pos);
+        self.next_objects.clear();
+        self.fill_next_objects();
+        self.held_object = None;
+        self.hold_used = false;
+        self.lock_started_at = None;
+        self.lock_resets = 0;
+        self.lowest_row = pos.y;
+        self.spawned_at = (direction, pos);
+        self.piece_inputs = 0;
+        self.last_rotation_kick = None;
+        self.scoring.reset();
+        self.last_clear = None;
+        self.last_stack_clear = None;
+        self.level = self.rules.start_level;
+        self.lines = 0;
+        self.score = 0;
+        self.garbage_rows = 0;
+        self.garbage_cleared = 0;
+        self.pieces = 0;
+        self.finesse_faults = 0;
+        self.paused = true;
+        self.game_over = false;
+        self.cleared = false;
+        self.frame = 0;
+        self.frame_remainder = Duration::ZERO;
+        self.last_tick_at = None;
+        self.last_fall_at = 0;
+        self.inputs.clear();
+        self.emit(GameEvent::Reset { seed });
+        self.add_garbage();
+
+        self.renderer.prerender(self);
+        self.renderer.render(self);
+    }
+
+    fn add_score(&mut self, points: u64) {
+        self.score += points;
+    }
+
+    /// Counts cleared lines, moving up a level whenever the mode says so.
+    fn add_lines(&mut self, lines: u16) {
+        self.lines += lines as u32;
+
+        let level = self.mode.level(&self.rules, self.lines);
+        if level > self.level {
+            self.level = level;
+            self.emit(GameEvent::LevelUp { level });
+        }
+    }
+
+    /// Pushes in the garbage rows the mode asks for, each with its hole in a random column other
+    /// than the one of the row below, and tops out the game if that pushes the stack out.
+    fn add_garbage(&mut self) {
+        let rows = self.mode.garbage(self);
+        if rows == 0 {
+            return;
+        }
+
+        let bottom = self.field.rows() as i16 - 1;
+        let mut last_hole = (self.garbage_rows > 0)
+            .then(|| (0..self.field.width).find(|&x| !self.field.is_occupied(x as i16, bottom)))
+            .flatten();
+        let mut overflow = false;
+        for _ in 0..rows {
+            let hole = match last_hole {
+                Some(last_hole) => {
+                    let hole = self.rng.random_range(0..self.field.width - 1);
+                    if hole >= last_hole { hole + 1 } else { hole }
+                }
+                None => self.rng.random_range(0..self.field.width),
+            };
+            overflow |= self.field.insert_garbage(1, hole);
+            last_hole = Some(hole);
+        }
+        self.garbage_rows = (self.garbage_rows + rows).min(self.field.height);
+        self.emit(GameEvent::GarbageAdded { rows });
+
+        if overflow && self.mode.ends_on_top_out() {
+            self.top_out();
+        } else if overflow {
+            let next_object = *self.next_objects.front().expect("The preview queue is never empty");
+            self.clear_stack_top(next_object);
+        }
+    }
+
+    /// Garbage rows left at the bottom of the field.
+    pub fn get_garbage_rows(&self) -> u16 {
+        self.garbage_rows
+    }
+
+    /// Garbage rows cleared so far.
+    pub fn get_garbage_cleared(&self) -> u32 {
+        self.garbage_cleared
+    }
+
+    /// Pieces locked so far.
+    pub fn get_pieces(&self) -> u32 {
+        self.pieces
+    }
+
+    /// Pieces locked per second of play.
+    pub fn get_pieces_per_second(&self) -> f64 {
+        let secs = self.get_time().as_secs_f64();
+        if secs > 0.0 { self.pieces as f64 / secs } else { 0.0 }
+    }
+
+    /// Pieces put into place with more moves and rotations than needed, see `finesse`.
+    pub fn get_finesse_faults(&self) -> u32 {
+        self.finesse_faults
+    }
+
+    /// Rows at the top of the stack that were just cleared away instead of topping out,
+    /// with how far along the animation of it is, from 0 to 1.
+    pub fn get_stack_clear(&self) -> Option<(u16, f64)> {
+        let rows = self.last_stack_clear?;
+        let elapsed = (self.time_ms() - self.last_stack_clear_at) as f64;
+        Some((rows, (elapsed / STACK_CLEAR_ANIMATION_MS as f64).clamp(0.0, 1.0)))
+    }
+
+    /// Time it currently takes the current object to fall one row on its own,
+    /// `Duration::MAX` if gravity is off.
+    pub fn gravity_interval(&self) -> Duration {
+        Duration::try_from_secs_f64(self.gravity.row_interval_ms(self.level) / 1000.0)
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// Adds an observer notified of every event from now on.
+    pub fn subscribe(&mut self, subscriber: Box<dyn Subscriber>) {
+        self.subscribers.push(subscriber);
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        for subscriber in self.subscribers.iter_mut() {
+            subscriber.notify(&event);
+        }
+    }
+
+    pub fn exit(&mut self) {
+        self.should_exit = true;
+    }
+
+    pub fn should_exit(&self) -> bool {
+        self.should_exit
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused != self.paused {
+            self.paused = paused;
+
+            self.renderer.prerender(self);
+            self.renderer.render(self);
+
+            self.emit(if paused {
+                GameEvent::Paused
+            } else {
+                GameEvent::Resumed
+            });
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    /// Whether the game ended by reaching the goal of its mode.
+    pub fn is_cleared(&self) -> bool {
+        self.cleared
+    }
+
+    /// Mode the game is played in, as chosen by its rules.
+    pub fn get_mode(&self) -> &dyn GameMode {
+        self.mode.as_ref()
+    }
+
+    /// Ends the game as cleared if it reached the goal of its mode, returning whether it did.
+    fn check_goal(&mut self) -> bool {
+        if !self.mode.is_goal_reached(self) {
+            return false;
+        }
+
+        self.game_over = true;
+        self.cleared = true;
+        self.emit(GameEvent::Cleared);
+        self.renderer.render(self);
+        true
+    }
+
+    /// Spawns the given tetromino at the top of the field, ending the game if there is no room left for it.
+    fn spawn(&mut self, (tetromino, direction): (Tetromino, Direction)) {
+        let mut start_pos = self.field.get_start_pos(&self.rules.pieces[tetromino], &direction);
+        if start_pos.is_none() && !self.mode.ends_on_top_out() {
+            self.clear_stack_top((tetromino, direction));
+            start_pos = self.field.get_start_pos(&self.rules.pieces[tetromino], &direction);
+        }
+
+        match start_pos {
+            Some(pos) => {
+                self.current_object = Object::new(&self.rules.pieces, tetromino, direction, pos);
+                self.lock_started_at = None;
+                self.lock_resets = 0;
+                self.lowest_row = pos.y;
+                self.spawned_at = (direction, pos);
+                self.piece_inputs = 0;
+                self.last_rotation_kick = None;
+                self.emit(GameEvent::PieceSpawned {
+                    tetromino,
+                    direction,
+                });
+            }
+
+            // If we cannot get a start position for the next tetromino, it means the game is over
+            None => self.top_out(),
+        }
+    }
+
+    /// Ends the game because the stack reached the top of the field.
+    fn top_out(&mut self) {
+        self.game_over = true;
+        let funny = self.rng.random_bool(FUNNY_GAME_OVER_CHANCE);
+        self.emit(GameEvent::GameOver { funny });
+    }
+
+    /// Clears away the buffer and the upper half of the field to make room, in modes that do not
+    /// top out. Further rows go until the given piece has room to spawn, which it has at the
+    /// latest once the whole field is empty.
+    fn clear_stack_top(&mut self, (tetromino, direction): (Tetromino, Direction)) {
+        let mut rows = self.field.height / 2;
+        self.field.clear_top_rows(self.field.buffer + rows);
+        let piece = &self.rules.pieces[tetromino];
+        while rows < self.field.height && self.field.get_start_pos(piece, &direction).is_none() {
+            rows += 1;
+            self.field.clear_top_rows(self.field.buffer + rows);
+        }
+        self.garbage_rows = self.garbage_rows.min(self.field.height - rows);
+        self.last_stack_clear = Some(rows);
+        self.last_stack_clear_at = self.time_ms();
+        self.emit(GameEvent::StackCleared { rows });
+    }
+
+    /// Deals pieces until the preview queue holds as many as the rules ask for.
+    fn fill_next_objects(&mut self) {
+        let preview_size = self.rules.preview_size.clamp(1, MAX_PREVIEW_SIZE);
+        while self.next_objects.len() < preview_size {
+            let next_object = (
+                self.randomizer.next_tetromino(&mut self.rng),
+                Direction::random(&mut self.rng),
+            );
+            self.next_objects.push_back(next_object);
+        }
+    }
+
+    /// Takes the first piece out of the preview queue, dealing a new one at its end.
+    fn take_next_object(&mut self) -> (Tetromino, Direction) {
+        let next_object = self
+            .next_objects
+            .pop_front()
+            .expect("The preview queue is never empty");
+        self.fill_next_objects();
+        next_object
+    }
+
+    pub fn next(&mut self) {
+        let next_object = self.take_next_object();
+        self.hold_used = false;
+        self.spawn(next_object);
+
+        self.renderer.render(self);
+    }
+
+    /// Whether the current object may still be swapped into the hold slot, which is allowed once per locked piece.
+    pub fn can_hold_current_object(&self) -> bool {
+        !self.hold_used
+    }
+
+    /// Swaps the current object with the held one, or with the next object if nothing is held yet.
+    pub fn hold_current_object(&mut self) {
+        if self.hold_used {
+            self.emit(GameEvent::HoldFailed);
+            return;
+        }
+
+        let current_tetromino = *self.current_object.get_type();
+        let new_object = match self.held_object.replace(current_tetromino) {
+            Some(held_tetromino) => (held_tetromino, Direction::Up),
+            None => self.take_next_object(),
+        };
+
+        self.hold_used = true;
+        self.score = self.score.saturating_sub(self.rules.hold_penalty);
+        self.emit(GameEvent::Held {
+            tetromino: current_tetromino,
+        });
+        self.spawn(new_object);
+
+        self.renderer.render(self);
+    }
+
+    pub fn rotate_current_object(&mut self) {
+        let new_direction = self.current_object.get_direction().copy_rotate();
+        self.rotate_current_object_to(new_direction);
+    }
+
+    pub fn rotate_current_object_ccw(&mut self) {
+        let new_direction = self.current_object.get_direction().copy_rotate_ccw();
+        self.rotate_current_object_to(new_direction);
+    }
+
+    pub fn rotate_current_object_180(&mut self) {
+        let new_direction = self.current_object.get_direction().copy_rotate_180();
+        self.rotate_current_object_to(new_direction);
+    }
+
+    /// Rotates the current object into the given direction, trying each wall kick until one fits.
+    fn rotate_current_object_to(&mut self, new_direction: Direction) {
+        let piece = &self.rules.pieces[*self.current_object.get_type()];
+        let new_qube = piece.qube(&new_direction);
+        let new_qube_width = piece.dimensions(&new_direction).0;
+
+        let kicked_pos = self
+            .current_object
+            .get_rotation_positions(&self.rules.pieces, &new_direction)
+            .into_iter()
+            .find(|(_, pos)| self.field.can_hold((&new_qube, new_qube_width), pos));
+
+        match kicked_pos {
+            Some((kick, pos)) => {
+                self.current_object.rotate_to(&self.rules.pieces, new_direction, pos);
+                self.last_rotation_kick = Some(kick);
+                self.on_moved_down();
+                self.reset_lock_delay();
+                self.emit(GameEvent::Rotated {
+                    direction: new_direction,
+                    kick,
+                });
+                self.renderer.render(self);
+            }
+            None => self.emit(GameEvent::RotateFailed),
+        }
+    }
+
+    /// Whether the current object fits into the field when moved by the given offset.
+    pub fn can_move_current_object(&self, x: i16, y: i16) -> bool {
+        self.current_object
+            .get_position()
+            .copy_offset(x, y)
+            .is_some_and(|pos| {
+                self.field
+                    .can_hold(self.current_object.get_qube_with_width(), &pos)
+            })
+    }
+
+    pub fn move_current_object_left(&mut self) {
+        if self.can_move_current_object(-1, 0) {
+            self.current_object.pos.mod_x(-1);
+            self.last_rotation_kick = None;
+            self.reset_lock_delay();
+            self.emit(GameEvent::Moved { x: -1, y: 0 });
+            self.renderer.render(self);
+        } else {
+            self.emit(GameEvent::MoveFailed);
+        }
+    }
+
+    pub fn move_current_object_right(&mut self) {
+        if self.can_move_current_object(1, 0) {
+            self.current_object.pos.mod_x(1);
+            self.last_rotation_kick = None;
+            self.reset_lock_delay();
+            self.emit(GameEvent::Moved { x: 1, y: 0 });
+            self.renderer.render(self);
+        } else {
+            self.emit(GameEvent::MoveFailed);
+        }
+    }
+
+    pub fn move_current_object_down(&mut self) {
+        if self.can_move_current_object(0, 1) {
+            self.current_object.pos.mod_y(1);
+            self.last_rotation_kick = None;
+            self.on_moved_down();
+            self.emit(GameEvent::Moved { x: 0, y: 1 });
+            self.add_score(SOFT_DROP_POINTS);
+            self.renderer.render(self);
+        }
+    }
+
+    fn is_current_object_landed(&self) -> bool {
+        !self.field.can_hold(
+            self.current_object.get_qube_with_width(),
+            &self.current_object.get_position().copy_mod_y(1),
+        )
+    }
+
+    /// Restarts a running lock delay after the current object was moved or rotated,
+    /// as long as it has resets left.
+    fn reset_lock_delay(&mut self) {
+        if self.lock_started_at.is_some() && self.lock_resets < self.rules.max_lock_resets {
+            self.lock_resets += 1;
+            self.lock_started_at = Some(self.time_ms());
+        }
+    }
+
+    /// Reaching a new lowest row stops the lock delay and gives all lock resets back.
+    fn on_moved_down(&mut self) {
+        let y = self.current_object.get_position().y;
+        if y > self.lowest_row {
+            self.lowest_row = y;
+            self.lock_resets = 0;
+            self.lock_started_at = None;
+        }
+    }
+
+    /// Checks the 3-corner rule for a T tetromino, or any piece its set marks as spinning,
+    /// that got into place by rotating. The corners are those of the box the piece turns in.
+    /// A T-spin is a mini one if only one of the corners the T points at is blocked,
+    /// unless it took the last, farthest kick to get there.
+    fn detect_t_spin(&self) -> TSpin {
+        let Some((kick_x, kick_y)) = self.last_rotation_kick else {
+            return TSpin::None;
+        };
+        let piece = &self.rules.pieces[*self.current_object.get_type()];
+        if !piece.can_spin() {
+            return TSpin::None;
+        }
+
+        let direction = self.current_object.get_direction();
+        let (offset_x, offset_y) = piece.pivot_offset(direction);
+        let pos = self.current_object.get_position();
+        let box_x = pos.x as i16 - offset_x as i16;
+        let box_y = pos.y as i16 - offset_y as i16;
+
+        let far = piece.box_size() as i16 - 1;
+        let (front_corners, back_corners) = match direction {
+            Direction::Up => ([(0, 0), (far, 0)], [(0, far), (far, far)]),
+            Direction::Right => ([(far, 0), (far, far)], [(0, 0), (0, far)]),
+            Direction::Down => ([(0, far), (far, far)], [(0, 0), (far, 0)]),
+            Direction::Left => ([(0, 0), (0, far)], [(far, 0), (far, far)]),
+        };
+        let count_occupied = |corners: [(i16, i16); 2]| {
+            corners
+                .iter()
+                .filter(|(x, y)| self.field.is_occupied(box_x + x, box_y + y))
+                .count()
+        };
+        let front = count_occupied(front_corners);
+        let back = count_occupied(back_corners);
+
+        if front + back < 3 {
+            TSpin::None
+        } else if front == 2 || (kick_x.abs() == 1 && kick_y.abs() == 2) {
+            TSpin::Full
+        } else {
+            TSpin::Mini
+        }
+    }
+
+    fn lock_current_object(&mut self) {
+        let t_spin = self.detect_t_spin();
+
+        // Dropped without a single move or rotation can never be a fault
+        if self.piece_inputs > 0 {
+            let tetromino = *self.current_object.get_type();
+            let placement = (*self.current_object.get_direction(), self.current_object.pos);
+            let spawned_at = self.spawned_at;
+            let fewest = finesse::fewest_inputs(
+                &self.field,
+                &self.rules.pieces,
+                tetromino,
+                spawned_at,
+                placement,
+            );
+            if fewest.is_some_and(|fewest| self.piece_inputs > fewest) {
+                self.finesse_faults += 1;
+            }
+        }
+        self.pieces += 1;
+
+        self.field
+            .place(
+                self.current_object.get_qube_with_width(),
+                self.current_object.get_position(),
+            )
+            .expect("Failed to place the current object in the field!");
+
+        self.emit(GameEvent::Locked {
+            tetromino: *self.current_object.get_type(),
+            t_spin,
+        });
+
+        // Garbage always stays at the bottom, so the full rows among it are the garbage cleared
+        let garbage_start = self.field.area.len() - self.garbage_rows as usize;
+        let garbage_cleared = self.field.area[garbage_start..]
+            .iter()
+            .filter(|&&row| row == Row::MAX)
+            .count() as u16;
+        let completed_lines = self.field.clear_completed_lines();
+        self.garbage_rows -= garbage_cleared;
+        self.garbage_cleared += garbage_cleared as u32;
+
+        let perfect_clear = completed_lines > 0 && self.field.is_empty();
+        // Points are scaled by the level before the clear, which may start at 0 on NES gravity
+        if let Some(clear) = self
+            .scoring
+            .lock(completed_lines, t_spin, perfect_clear, self.level.max(1))
+        {
+            self.last_clear = Some(clear);
+            self.last_clear_at = self.time_ms();
+            let points = self.mode.clear_points(&clear);
+            self.add_score(points);
+            if completed_lines > 0 {
+                self.emit(GameEvent::LinesCleared {
+                    count: completed_lines,
+                    kind: clear,
+                });
+            }
+        }
+        self.add_lines(completed_lines);
+        if self.check_goal() {
+            return;
+        }
+        self.add_garbage();
+        if self.game_over {
+            self.renderer.render(self);
+            return;
+        }
+
+        // Move to the next object
+        self.next();
+    }
+
+    /// Position where the current object would land if it was dropped down.
+    pub fn ghost_position(&self) -> Pos {
+        let qube_with_width = self.current_object.get_qube_with_width();
+        let mut pos = *self.current_object.get_position();
+
+        while self.field.can_hold(qube_with_width, &pos.copy_mod_y(1)) {
+            pos.mod_y(1);
+        }
+
+        pos
+    }
+
+    pub fn drop_current_object_down(&mut self) {
+        let ghost_pos = self.ghost_position();
+        let skipped_lines = ghost_pos.y - self.current_object.get_position().y;
+        self.current_object.pos = ghost_pos;
+        if skipped_lines > 0 {
+            self.last_rotation_kick = None;
+        }
+
+        self.emit(GameEvent::HardDropped {
+            rows: skipped_lines,
+        });
+
+        // Dropped objects lock right away, without waiting for the lock delay
+        self.add_score(skipped_lines as u64 * HARD_DROP_POINTS);
+        self.lock_current_object();
+    }
+
+    /// Applies a player input to the current object, recording it for the replay.
+    /// Inputs are ignored while the game is paused or over.
+    pub fn apply(&mut self, input: Input) {
+        if self.paused || self.game_over {
+            return;
+        }
+
+        self.inputs.push((self.frame, input));
+        if !matches!(input, Input::SoftDrop | Input::HardDrop | Input::Hold) {
+            self.piece_inputs += 1;
+        }
+        match input {
+            Input::MoveLeft => self.move_current_object_left(),
+            Input::MoveRight => self.move_current_object_right(),
+            Input::SoftDrop => self.move_current_object_down(),
+            Input::RotateCw => self.rotate_current_object(),
+            Input::RotateCcw => self.rotate_current_object_ccw(),
+            Input::Rotate180 => self.rotate_current_object_180(),
+            Input::HardDrop => self.drop_current_object_down(),
+            Input::Hold => self.hold_current_object(),
+        }
+    }
+
+    /// Recording of the game so far, which plays out the same way from its seed and inputs.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            seed: self.seed,
+            rules: self.rules.clone(),
+            inputs: self.inputs.clone(),
+            result: ReplayResult::of(self),
+        }
+    }
+
+    /// Replaces the clock `tick` reads the time from.
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+        self.last_tick_at = None;
+    }
+
+    /// Frames played so far. Time does not pass while the game is paused or over.
+    pub fn get_frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Time played so far, counted in whole milliseconds of whole frames.
+    pub fn get_time(&self) -> Duration {
+        Duration::from_millis(self.time_ms() as u64)
+    }
+
+    /// Milliseconds played so far, counted in whole frames.
+    fn time_ms(&self) -> i64 {
+        (self.frame * 1000 / FRAMES_PER_SECOND) as i64
+    }
+
+    /// Advances the game by the time its clock moved on since the last tick.
+    pub fn tick(&mut self) {
+        let now = self.clock.now();
+        if let Some(last_tick_at) = self.last_tick_at.replace(now) {
+            self.update(now.saturating_sub(last_tick_at));
+        }
+    }
+
+    /// Advances the game by `dt`, one fixed frame at a time.
+    /// Time left over from the last frame is kept for the next call, so the same steps always
+    /// play out the same way, however they are split up.
+    pub fn update(&mut self, dt: Duration) {
+        if self.paused || self.game_over {
+            // Time stands still while the game is paused or over
+            return;
+        }
+
+        self.frame_remainder += dt;
+        while self.frame_remainder >= FRAME_DURATION && !self.game_over {
+            self.frame_remainder -= FRAME_DURATION;
+            self.step();
+        }
+    }
+
+    /// Plays a single frame.
+    fn step(&mut self) {
+        self.frame += 1;
+        if self.check_goal() {
+            return;
+        }
+        let time = self.time_ms();
+
+        if self.last_clear.is_some() && time - self.last_clear_at >= LINE_CLEAR_DISPLAY_MS {
+            self.last_clear = None;
+            self.renderer.render(self);
+        }
+        let stack_clear_done = time - self.last_stack_clear_at >= STACK_CLEAR_ANIMATION_MS;
+        if self.last_stack_clear.is_some() && stack_clear_done {
+            self.last_stack_clear = None;
+            self.renderer.render(self);
+        }
+
+        // A landed object waits for the lock delay instead of locking on the next gravity tick
+        if self.is_current_object_landed() {
+            match self.lock_started_at {
+                None => self.lock_started_at = Some(time),
+                Some(started_at) if time - started_at >= self.rules.lock_delay_ms => {
+                    self.lock_current_object();
+                    return;
+                }
+                Some(_) => {}
+            }
+        } else {
+            // The object was moved off the ledge it landed on
+            self.lock_started_at = None;
+        }
+
+        // Fast gravity moves down several rows per tick, 20G goes straight onto the stack
+        let interval = self.gravity.row_interval_ms(self.level);
+        let rows = if interval > 0.0 {
+            ((time - self.last_fall_at) as f64 / interval).floor()
+        } else {
+            self.field.rows() as f64
+        };
+        if rows < 1.0 {
+            // A running timer or animation is drawn on every frame, not only when something moved
+            if self.mode.timer(self).is_some() || self.last_stack_clear.is_some() {
+                self.renderer.render(self);
+            }
+            return;
+        }
+
+        let mut fallen_rows = 0;
+        for _ in 0..(rows as u16).min(self.field.rows()) {
+            if self.is_current_object_landed() {
+                break;
+            }
+            self.current_object.pos.mod_y(1);
+            fallen_rows += 1;
+        }
+        if fallen_rows > 0 {
+            self.last_rotation_kick = None;
+            self.on_moved_down();
+            self.emit(GameEvent::Fell { rows: fallen_rows });
+        }
+
+        // Keep the leftover time, so gravity slower than a frame does not lose any of it
+        self.last_fall_at = if interval > 0.0 {
+            self.last_fall_at + (rows * interval) as i64
+        } else {
+            time
+        };
+        self.renderer.render(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bot::Bot;
+    use crate::game::consts::field::Row;
+    use crate::game::clock::ManualClock;
+    use std::sync::mpsc;
+    use crate::game::gravity::GravityKind;
+    use crate::game::piece_set::PieceSet;
+    use crate::game::rendering::NullRenderer;
+    use std::sync::Arc;
+
+    fn new_game() -> Game {
+        new_game_with_seed(42)
+    }
+
+    fn new_game_with_seed(seed: u64) -> Game {
+        Game::new(
+            Box::new(NullRenderer),
+            Rules::default(),
+            seed,
+        )
+    }
+
+    /// Plays a game by locking every piece as soon as it spawns, returning the dealt pieces.
+    fn deal_pieces(game: &mut Game, count: usize) -> Vec<(Tetromino, Direction)> {
+        (0..count)
+            .map(|_| {
+                let dealt = (
+                    *game.current_object.get_type(),
+                    *game.current_object.get_direction(),
+                );
+                game.next();
+                dealt
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_same_seed_deals_same_pieces() {
+        let mut game = new_game_with_seed(1234);
+        let mut other_game = new_game_with_seed(1234);
+
+        assert_eq!(deal_pieces(&mut game, 50), deal_pieces(&mut other_game, 50));
+    }
+
+    #[test]
+    fn test_different_seeds_deal_different_pieces() {
+        let mut game = new_game_with_seed(1);
+        let mut other_game = new_game_with_seed(2);
+
+        assert_ne!(deal_pieces(&mut game, 50), deal_pieces(&mut other_game, 50));
+    }
+
+    #[test]
+    fn test_reset_with_seed_replays_game() {
+        let mut game = new_game_with_seed(7);
+        let pieces = deal_pieces(&mut game, 20);
+
+        game.reset_with_seed(7);
+        assert_eq!(game.get_seed(), 7);
+        assert_eq!(deal_pieces(&mut game, 20), pieces);
+
+        // A plain reset moves on to a new, but still deterministic, seed
+        let mut other_game = new_game_with_seed(7);
+        deal_pieces(&mut other_game, 20);
+        game.reset();
+        other_game.reset();
+        assert_ne!(game.get_seed(), 7);
+        assert_eq!(game.get_seed(), other_game.get_seed());
+    }
+
+    #[test]
+    fn test_hold_takes_next_object_when_empty() {
+        let mut game = new_game();
+        let current = *game.current_object.get_type();
+        let next = game.next_objects[0].0;
+
+        game.hold_current_object();
+
+        assert_eq!(game.held_object, Some(current));
+        assert_eq!(*game.current_object.get_type(), next);
+        assert!(!game.can_hold_current_object());
+    }
+
+    #[test]
+    fn test_preview_queue() {
+        let mut game = new_game();
+        assert_eq!(game.next_objects.len(), game.rules.preview_size);
+
+        // Pieces move up the queue as they are dealt
+        let queued: Vec<_> = game.next_objects.iter().copied().collect();
+        game.next();
+        assert_eq!(
+            (*game.current_object.get_type(), *game.current_object.get_direction()),
+            queued[0]
+        );
+        let moved_up: Vec<_> = game.next_objects.iter().take(queued.len() - 1).copied().collect();
+        assert_eq!(moved_up, queued[1..]);
+        assert_eq!(game.next_objects.len(), game.rules.preview_size);
+    }
+
+    #[test]
+    fn test_preview_size_does_not_change_pieces() {
+        let mut game = new_game();
+        let mut other_game = Game::new(
+            Box::new(NullRenderer),
+            Rules {
+                preview_size: 1,
+                ..Rules::default()
+            },
+            42,
+        );
+        assert_eq!(other_game.next_objects.len(), 1);
+
+        assert_eq!(deal_pieces(&mut game, 30), deal_pieces(&mut other_game, 30));
+    }
+
+    #[test]
+    fn test_plays_on_any_field_size() {
+        for (width, height, buffer) in [(4, 20, 2), (20, 20, 2), (10, 40, 4), (10, 20, 0)] {
+            let rules = Rules {
+                field_width: width,
+                field_height: height,
+                field_buffer: buffer,
+                ..Rules::default()
+            };
+            let mut game = Game::new(Box::new(NullRenderer), rules, 42);
+            game.set_paused(false);
+            assert_eq!((game.field.width, game.field.height), (width, height));
+            assert_eq!(game.current_object.pos.y + 1, buffer.max(1));
+
+            let mut bot = Bot::default();
+            while game.get_pieces() < 60 && !game.is_game_over() {
+                game.apply(bot.next_input(&game));
+                game.update(FRAME_DURATION);
+            }
+            assert!(game.lines > 0, "no lines cleared on {}x{}", width, height);
+            assert!(game.replay().verify().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_plays_with_any_piece_set() {
+        for name in PieceSet::BUILTIN {
+            let pieces = PieceSet::builtin(name).unwrap();
+            let rules = Rules {
+                pieces: pieces.clone(),
+                field_width: MIN_FIELD_WIDTH,
+                ..Rules::default()
+            };
+            let mut game = Game::new(Box::new(NullRenderer), rules, 42);
+            game.set_paused(false);
+            // Too narrow a field is widened to fit the largest piece
+            assert_eq!(game.field.width, pieces.get_max_size().max(MIN_FIELD_WIDTH));
+
+            let mut bot = Bot::default();
+            let mut dealt = Vec::new();
+            while game.get_pieces() < 60 && !game.is_game_over() {
+                let tetromino = *game.current_object.get_type();
+                if !dealt.contains(&tetromino) {
+                    dealt.push(tetromino);
+                }
+                game.apply(bot.next_input(&game));
+                game.update(FRAME_DURATION);
+            }
+            let in_set = |&tetromino: &Tetromino| pieces.get(tetromino.index()) == Some(tetromino);
+            assert!(dealt.iter().all(in_set));
+            assert!(game.get_pieces() > 10, "{} game ended early", name);
+            assert!(game.replay().verify().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_short_field_fits_every_piece() {
+        let source = format!("piece I cyan\nXXXXXXXX\n{}", "........\n".repeat(7));
+        let tall = Arc::new(PieceSet::parse("tall", &source).unwrap());
+        // Too low a field is raised to fit the largest piece, tall pieces spawn high in the buffer
+        let pentominoes = PieceSet::builtin("pentomino").unwrap();
+        let cases = [(pentominoes, 0, 5), (tall.clone(), 0, 8), (tall, 4, 8)];
+        for (pieces, buffer, rows) in cases {
+            let rules = Rules {
+                pieces: pieces.clone(),
+                field_height: MIN_FIELD_HEIGHT,
+                field_buffer: buffer,
+                ..Rules::default()
+            };
+            let game = Game::new(Box::new(NullRenderer), rules, 7);
+            assert_eq!(game.field.rows(), rows);
+            let directions = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+            for tetromino in pieces.tetrominoes() {
+                for direction in &directions {
+                    assert!(game.field.get_start_pos(&pieces[tetromino], direction).is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hold_once_per_piece() {
+        let mut game = new_game();
+        game.hold_current_object();
+        let held = game.held_object;
+        let current = *game.current_object.get_type();
+
+        // A second swap before the piece locks is rejected
+        game.hold_current_object();
+        assert_eq!(game.held_object, held);
+        assert_eq!(*game.current_object.get_type(), current);
+
+        // Locking a piece allows holding again, swapping back the held tetromino
+        game.next();
+        let current = *game.current_object.get_type();
+        assert!(game.can_hold_current_object());
+
+        game.hold_current_object();
+        assert_eq!(game.held_object, Some(current));
+        assert_eq!(Some(*game.current_object.get_type()), held);
+        assert_eq!(*game.current_object.get_direction(), Direction::Up);
+    }
+
+    #[test]
+    fn test_hold_penalty() {
+        let mut game = new_game();
+        game.score = 120;
+
+        game.hold_current_object();
+        assert_eq!(game.score, 120 - game.rules.hold_penalty);
+
+        // The score never drops below zero
+        game.next();
+        game.score = 10;
+        game.hold_current_object();
+        assert_eq!(game.score, 0);
+    }
+
+    #[test]
+    fn test_ghost_position() {
+        let mut game = new_game();
+        game.current_object =
+            Object::new(&game.rules.pieces, Tetromino::O, Direction::Up, Pos::new(4, 0).unwrap());
+
+        // On an empty field the ghost rests on the floor
+        let ghost_pos = game.ghost_position();
+        assert_eq!(ghost_pos.x, 4);
+        assert_eq!(ghost_pos.y, game.field.rows() - 2);
+
+        // The ghost lands on top of the stack
+        let stack = game.field.rows() as usize - 5;
+        game.field.area[stack] = game.field.empty_row() | 0b0000_0000_0011_0000;
+        assert_eq!(game.ghost_position().y as usize, stack - 2);
+
+        // Dropping moves the current object onto the ghost
+        game.drop_current_object_down();
+        let area = &game.field.area;
+        let empty_row = game.field.empty_row();
+        assert_eq!(area[stack - 2] & area[stack - 1], 0b0000_0000_0011_0000 | empty_row);
+    }
+
+    /// Puts an O tetromino on the floor of an empty field and unpauses the game.
+    fn land_o_tetromino(game: &mut Game) {
+        let floor = game.field.rows() - 2;
+        game.current_object = Object::new(
+            &game.rules.pieces,
+            Tetromino::O,
+            Direction::Up,
+            Pos::new(4, floor).unwrap(),
+        );
+        game.lowest_row = floor;
+        game.paused = false;
+    }
+
+    #[test]
+    fn test_landed_object_waits_for_lock_delay() {
+        let mut game = new_game();
+        land_o_tetromino(&mut game);
+        let lock_delay = Duration::from_millis(game.rules.lock_delay_ms as u64);
+
+        // Landing starts the lock delay instead of locking
+        game.update(FRAME_DURATION);
+        assert!(game.lock_started_at.is_some());
+        assert!(game.field.is_empty());
+
+        game.update(lock_delay - FRAME_DURATION * 2);
+        assert!(game.field.is_empty());
+
+        // The object locks once the delay ran out
+        game.update(FRAME_DURATION * 2);
+        assert!(!game.field.is_empty());
+        assert!(game.lock_started_at.is_none());
+    }
+
+    #[test]
+    fn test_moves_reset_lock_delay() {
+        let mut game = new_game();
+        land_o_tetromino(&mut game);
+        game.update(FRAME_DURATION);
+        let started_at = game.lock_started_at.unwrap();
+
+        game.update(Duration::from_millis(300));
+        game.move_current_object_left();
+        assert!(game.lock_started_at.unwrap() > started_at);
+        assert_eq!(game.lock_resets, 1);
+
+        // Once all resets are used up, moving no longer restarts the delay
+        game.lock_resets = game.rules.max_lock_resets;
+        let started_at = game.lock_started_at;
+        game.update(Duration::from_millis(300));
+        game.move_current_object_right();
+        assert_eq!(game.lock_started_at, started_at);
+        assert!(game.field.is_empty());
+
+        game.update(Duration::from_millis(300));
+        assert!(!game.field.is_empty());
+    }
+
+    #[test]
+    fn test_new_lowest_row_gives_resets_back() {
+        let mut game = new_game();
+        game.current_object =
+            Object::new(&game.rules.pieces, Tetromino::O, Direction::Up, Pos::new(4, 5).unwrap());
+        game.lowest_row = 5;
+        game.lock_started_at = Some(0);
+        game.lock_resets = 3;
+
+        game.move_current_object_down();
+        assert_eq!(game.lowest_row, 6);
+        assert_eq!(game.lock_resets, 0);
+        assert!(game.lock_started_at.is_none());
+    }
+
+    #[test]
+    fn test_hard_drop_locks_landed_object() {
+        let mut game = new_game();
+        land_o_tetromino(&mut game);
+
+        game.drop_current_object_down();
+        assert!(!game.field.is_empty());
+    }
+
+    /// Builds a T-spin double slot at the bottom of the field, with the T already rotated into it.
+    fn set_up_t_spin_double(game: &mut Game) {
+        let bottom = game.field.rows() - 1;
+        game.field.area[bottom as usize - 2] = game.field.empty_row() | 1 << 6;
+        game.field.area[bottom as usize - 1] = Row::MAX & !(0b111 << 4);
+        game.field.area[bottom as usize] = Row::MAX & !(1 << 5);
+        let pos = Pos::new(3, bottom - 1).unwrap();
+        game.current_object = Object::new(&game.rules.pieces, Tetromino::T, Direction::Down, pos);
+        game.last_rotation_kick = Some((0, 0));
+    }
+
+    #[test]
+    fn test_t_spin_double() {
+        let mut game = new_game();
+        set_up_t_spin_double(&mut game);
+
+        game.drop_current_object_down();
+
+        let clear = game.last_clear.expect("The T-spin should be reported");
+        assert_eq!(clear.t_spin, TSpin::Full);
+        assert_eq!(clear.lines, 2);
+        assert_eq!(game.score, 1200);
+        assert_eq!(game.field.area.last(), Some(&(game.field.empty_row() | 1 << 6)));
+    }
+
+    #[test]
+    fn test_no_t_spin_without_rotation() {
+        let mut game = new_game();
+        set_up_t_spin_double(&mut game);
+        game.last_rotation_kick = None;
+
+        game.drop_current_object_down();
+
+        let clear = game.last_clear.unwrap();
+        assert_eq!(clear.t_spin, TSpin::None);
+        assert_eq!(clear.title(), "DOUBLE");
+    }
+
+    #[test]
+    fn test_rotate_both_ways() {
+        let mut game = new_game();
+        game.current_object =
+            Object::new(&game.rules.pieces, Tetromino::T, Direction::Up, Pos::new(3, 5).unwrap());
+
+        game.rotate_current_object_ccw();
+        assert_eq!(*game.current_object.get_direction(), Direction::Left);
+        game.rotate_current_object();
+        assert_eq!(*game.current_object.get_direction(), Direction::Up);
+        assert_eq!(game.current_object.get_position().x, 3);
+
+        game.rotate_current_object_180();
+        assert_eq!(*game.current_object.get_direction(), Direction::Down);
+    }
+
+    #[test]
+    fn test_rotate_180_kicks_off_the_floor() {
+        let mut game = new_game();
+        let floor = game.field.rows() - 2;
+        // A flat T resting on the floor has no room to point down without moving up a row
+        game.current_object = Object::new(
+            &game.rules.pieces,
+            Tetromino::T,
+            Direction::Up,
+            Pos::new(3, floor).unwrap(),
+        );
+
+        game.rotate_current_object_180();
+        assert_eq!(*game.current_object.get_direction(), Direction::Down);
+        assert_eq!(game.current_object.get_position().y, floor);
+        assert_eq!(game.last_rotation_kick, Some((0, -1)));
+    }
+
+    #[test]
+    fn test_levels_advance_on_cleared_lines() {
+        let mut game = new_game();
+        assert_eq!(game.level, 1);
+
+        // Soft dropping scores points, but no longer levels up
+        game.add_score(10_000);
+        assert_eq!(game.level, 1);
+
+        game.add_lines(9);
+        assert_eq!(game.level, 1);
+        game.add_lines(1);
+        assert_eq!(game.level, 2);
+        game.add_lines(25);
+        assert_eq!((game.lines, game.level), (35, 4));
+    }
+
+    #[test]
+    fn test_start_level() {
+        let mut game = Game::new(
+            Box::new(NullRenderer),
+            Rules {
+                start_level: 0,
+                gravity: GravityKind::Nes,
+                ..Rules::default()
+            },
+            42,
+        );
+        assert_eq!(game.level, 0);
+        assert!(game.gravity_interval() > Duration::from_millis(700));
+
+        game.add_lines(10);
+        assert_eq!(game.level, 1);
+
+        game.reset();
+        assert_eq!((game.lines, game.level), (0, 0));
+    }
+
+    #[test]
+    fn test_fast_gravity_falls_several_rows_per_tick() {
+        let mut game = new_game();
+        game.current_object =
+            Object::new(&game.rules.pieces, Tetromino::O, Direction::Up, Pos::new(4, 0).unwrap());
+        game.level = 15;
+        game.paused = false;
+
+        // About 7ms per row on level 15
+        game.update(Duration::from_millis(70));
+        assert!(game.current_object.get_position().y >= 9);
+    }
+
+    #[test]
+    fn test_instant_gravity_drops_onto_stack() {
+        let mut game = new_game();
+        game.gravity = GravityKind::Instant.create();
+        game.current_object =
+            Object::new(&game.rules.pieces, Tetromino::O, Direction::Up, Pos::new(4, 0).unwrap());
+        game.paused = false;
+
+        game.update(FRAME_DURATION);
+        assert_eq!(game.current_object.get_position().y, game.ghost_position().y);
+        // It still waits for the lock delay
+        assert!(game.field.is_empty());
+    }
+
+    #[test]
+    fn test_finesse_faults() {
+        let mut game = new_game();
+        game.paused = false;
+
+        game.apply(Input::HardDrop);
+        assert_eq!((game.get_pieces(), game.get_finesse_faults()), (1, 0));
+
+        // Going back and forth ends up where the piece could have dropped from right away
+        game.apply(Input::MoveLeft);
+        game.apply(Input::MoveRight);
+        game.apply(Input::HardDrop);
+        assert_eq!((game.get_pieces(), game.get_finesse_faults()), (2, 1));
+    }
+
+    #[test]
+    fn test_objects_stay_put_without_gravity() {
+        let mut game = new_game();
+        game.gravity = GravityKind::Off.create();
+        game.paused = false;
+        let y = game.current_object.get_position().y;
+
+        game.update(Duration::from_secs(60));
+        assert_eq!(game.current_object.get_position().y, y);
+        assert_eq!(game.gravity_interval(), Duration::MAX);
+    }
+
+    #[test]
+    fn test_update_steps_whole_frames() {
+        let mut game = new_game();
+        game.paused = false;
+
+        game.update(FRAME_DURATION / 2);
+        assert_eq!(game.get_frame(), 0);
+        game.update(FRAME_DURATION / 2);
+        assert_eq!(game.get_frame(), 1);
+
+        game.update(Duration::from_secs(1));
+        assert_eq!(game.get_frame(), 1 + FRAMES_PER_SECOND);
+    }
+
+    #[test]
+    fn test_update_does_not_depend_on_step_size() {
+        let mut game = new_game();
+        let mut other_game = new_game();
+        game.paused = false;
+        other_game.paused = false;
+
+        game.update(Duration::from_secs(3));
+        for _ in 0..3000 {
+            other_game.update(Duration::from_millis(1));
+        }
+
+        assert_eq!(game.get_frame(), other_game.get_frame());
+        let y = game.current_object.get_position().y;
+        assert_eq!(y, other_game.current_object.get_position().y);
+        assert_eq!(game.field.area, other_game.field.area);
+    }
+
+    #[test]
+    fn test_time_stands_still_while_paused() {
+        let mut game = new_game();
+        assert!(game.is_paused());
+
+        game.update(Duration::from_secs(10));
+        assert_eq!(game.get_frame(), 0);
+    }
+
+    #[test]
+    fn test_tick_follows_clock() {
+        let mut game = new_game();
+        let clock = ManualClock::new();
+        game.set_clock(Box::new(clock.clone()));
+        game.paused = false;
+        let y = game.current_object.get_position().y;
+
+        // The first tick only starts counting
+        clock.advance(Duration::from_secs(5));
+        game.tick();
+        assert_eq!(game.get_frame(), 0);
+
+        clock.advance(Duration::from_secs(1));
+        game.tick();
+        assert_eq!(game.get_frame(), FRAMES_PER_SECOND);
+        assert_eq!(game.current_object.get_position().y, y + 1);
+    }
+
+    #[test]
+    fn test_simulate_whole_game() {
+        let mut game = new_game();
+        game.paused = false;
+
+        // Without any input, pieces pile up in the middle until the game is over
+        for _ in 0..10_000 {
+            game.update(Duration::from_secs(1));
+            if game.is_game_over() {
+                break;
+            }
+        }
+
+        assert!(game.is_game_over());
+        let frame = game.get_frame();
+        game.update(Duration::from_secs(1));
+        assert_eq!(game.get_frame(), frame);
+    }
+
+    #[test]
+    fn test_events_reach_subscribers() {
+        let mut game = new_game();
+        let (sender, receiver) = mpsc::channel();
+        game.subscribe(Box::new(sender));
+        set_up_t_spin_double(&mut game);
+
+        game.set_paused(false);
+        game.drop_current_object_down();
+        game.hold_current_object();
+        game.hold_current_object();
+
+        let events: Vec<GameEvent> = receiver.try_iter().collect();
+        assert_eq!(events[0], GameEvent::Resumed);
+        assert_eq!(events[1], GameEvent::HardDropped { rows: 0 });
+        assert_eq!(
+            events[2],
+            GameEvent::Locked {
+                tetromino: Tetromino::T,
+                t_spin: TSpin::Full
+            }
+        );
+        assert!(matches!(
+            events[3],
+            GameEvent::LinesCleared { count: 2, kind } if kind.points == 1200
+        ));
+        assert!(matches!(events[4], GameEvent::PieceSpawned { .. }));
+        assert!(matches!(events[5], GameEvent::Held { .. }));
+        assert!(matches!(events[6], GameEvent::PieceSpawned { .. }));
+        assert_eq!(events[7], GameEvent::HoldFailed);
+        assert_eq!(events.len(), 8);
+    }
+
+    #[test]
+    fn test_gravity_and_moves_are_reported() {
+        let mut game = new_game();
+        let (sender, receiver) = mpsc::channel();
+        game.subscribe(Box::new(sender));
+        game.current_object =
+            Object::new(&game.rules.pieces, Tetromino::O, Direction::Up, Pos::new(4, 5).unwrap());
+        game.paused = false;
+
+        game.update(Duration::from_secs(1));
+        game.move_current_object_left();
+        game.current_object.pos.x = 0;
+        game.move_current_object_left();
+
+        let events: Vec<GameEvent> = receiver.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::Fell { rows: 1 },
+                GameEvent::Moved { x: -1, y: 0 },
+                GameEvent::MoveFailed,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_game_over_is_reported() {
+        let mut game = new_game();
+        let (sender, receiver) = mpsc::channel();
+        game.subscribe(Box::new(sender));
+        game.paused = false;
+
+        while !game.is_game_over() {
+            game.update(Duration::from_secs(1));
+        }
+
+        let last_event = receiver.try_iter().last();
+        assert!(matches!(last_event, Some(GameEvent::GameOver { .. })));
+    }
+}
@@ -0,0 +1,539 @@
+use super::rules::Rules;
+use super::scoring::LineClear;
+use super::Game;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Lines to clear for finishing a Marathon.
+pub const MARATHON_LINES: u32 = 150;
+/// Lines to clear for finishing a Sprint.
+pub const SPRINT_LINES: u32 = 40;
+/// Time an Ultra game lasts.
+pub const ULTRA_DURATION: Duration = Duration::from_secs(180);
+/// Garbage rows to clear for finishing a Dig race.
+pub const DIG_LINES: u32 = 40;
+/// Garbage rows kept in the field during a Dig race, as long as there are more to dig.
+/// Fields lower than twice this keep them to half their height.
+pub const DIG_GARBAGE_HEIGHT: u16 = 10;
+
+/// What results are ranked by on the high score table of a mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ranking {
+    /// Highest score first, counting every finished game.
+    Score,
+    /// Fastest time first, only counting games that reached the goal.
+    Time,
+}
+
+/// Goal of a game, deciding when it ends, what the player sees of it and how it is scored.
+/// Modes keep no state of their own, everything they need is read from the game.
+pub trait GameMode: Send + Sync {
+    /// Whether the game reached the goal of the mode, which ends it as cleared.
+    /// Checked on every frame and after every locked piece.
+    fn is_goal_reached(&self, game: &Game) -> bool;
+
+    /// Time shown to the player, counting up or down, in modes played against the clock.
+    fn timer(&self, _game: &Game) -> Option<Duration> {
+        None
+    }
+
+    /// Stats shown in the sidebar, as a label and a value each.
+    fn hud(&self, game: &Game) -> [(&'static str, String); 3];
+
+    /// Title of the screen shown when the goal is reached.
+    fn clear_title(&self) -> &'static str {
+        "CLEAR"
+    }
+
+    /// Results listed on the screen shown when the game ends.
+    fn results(&self, game: &Game) -> Vec<(&'static str, String)> {
+        vec![
+            ("Score", game.score.to_string()),
+            ("Level", game.level.to_string()),
+            ("Lines", game.lines.to_string()),
+            ("Time", format_timer(game.get_time())),
+        ]
+    }
+
+    /// Level the game is on after clearing the given lines in total.
+    fn level(&self, rules: &Rules, lines: u32) -> u8 {
+        let levels_gained = lines / rules.lines_per_level.max(1);
+        rules
+            .start_level
+            .saturating_add(levels_gained.min(u8::MAX as u32) as u8)
+    }
+
+    /// Garbage rows to push in under the stack, asked for when the game starts and after every
+    /// locked piece. Garbage is expected to stay clear of where the pieces spawn.
+    fn garbage(&self, _game: &Game) -> u16 {
+        0
+    }
+
+    /// Whether running out of room for the next piece ends the game. Otherwise the upper half of
+    /// the stack, or more if the piece needs it, is cleared away and play goes on.
+    fn ends_on_top_out(&self) -> bool {
+        true
+    }
+
+    /// Points the given line clear or T-spin is worth.
+    fn clear_points(&self, clear: &LineClear) -> u64 {
+        clear.points
+    }
+
+    fn ranking(&self) -> Ranking {
+        Ranking::Score
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModeKind {
+    /// Clear 150 lines, speeding up every level.
+    Marathon,
+    /// Clear 40 lines as fast as possible.
+    Sprint,
+    /// Score as many points as possible in 3 minutes.
+    Ultra,
+    /// Clear 40 rows of garbage as fast as possible.
+    Dig,
+    /// Play until the stack tops out.
+    #[default]
+    Endless,
+    /// Play without topping out or speeding up.
+    Zen,
+}
+
+impl ModeKind {
+    /// In the order the mode select screen lists them.
+    pub const ALL: [ModeKind; 6] = [
+        ModeKind::Marathon,
+        ModeKind::Sprint,
+        ModeKind::Ultra,
+        ModeKind::Dig,
+        ModeKind::Endless,
+        ModeKind::Zen,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModeKind::Marathon => "marathon",
+            ModeKind::Sprint => "sprint",
+            ModeKind::Ultra => "ultra",
+            ModeKind::Dig => "dig",
+            ModeKind::Endless => "endless",
+            ModeKind::Zen => "zen",
+        }
+    }
+
+    /// Goal of the mode in a few words, as listed on the mode select screen.
+    pub fn description(&self) -> &'static str {
+        match self {
+            ModeKind::Marathon => "Clear 150 lines",
+            ModeKind::Sprint => "40 lines, fast",
+            ModeKind::Ultra => "3 minute score",
+            ModeKind::Dig => "Dig out 40 rows",
+            ModeKind::Endless => "Until you top out",
+            ModeKind::Zen => "Relax, no top out",
+        }
+    }
+
+    pub fn create(&self) -> Box<dyn GameMode> {
+        match self {
+            ModeKind::Marathon => Box::new(Marathon),
+            ModeKind::Sprint => Box::new(Sprint),
+            ModeKind::Ultra => Box::new(Ultra),
+            ModeKind::Dig => Box::new(Dig),
+            ModeKind::Endless => Box::new(Endless),
+            ModeKind::Zen => Box::new(Zen),
+        }
+    }
+}
+
+impl Display for ModeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for ModeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ModeKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = ModeKind::ALL.iter().map(|kind| kind.name()).collect();
+                format!("Unknown mode '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Formats a time to the millisecond, such as `1:23.456`.
+pub fn format_timer(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!("{}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+pub struct Marathon;
+
+impl GameMode for Marathon {
+    fn is_goal_reached(&self, game: &Game) -> bool {
+        game.lines >= MARATHON_LINES
+    }
+
+    fn hud(&self, game: &Game) -> [(&'static str, String); 3] {
+        [
+            ("Score", game.score.to_string()),
+            ("Level", game.level.to_string()),
+            ("Lines", format!("{}/{}", game.lines, MARATHON_LINES)),
+        ]
+    }
+
+    fn clear_title(&self) -> &'static str {
+        "MARATHON CLEAR"
+    }
+}
+
+/// Keeps the level it starts on, so only the player's speed decides the time.
+pub struct Sprint;
+
+impl GameMode for Sprint {
+    fn is_goal_reached(&self, game: &Game) -> bool {
+        game.lines >= SPRINT_LINES
+    }
+
+    fn timer(&self, game: &Game) -> Option<Duration> {
+        Some(game.get_time())
+    }
+
+    fn hud(&self, game: &Game) -> [(&'static str, String); 3] {
+        [
+            ("Time", format_timer(game.get_time())),
+            ("Lines", format!("{}/{}", game.lines, SPRINT_LINES)),
+            ("Score", game.score.to_string()),
+        ]
+    }
+
+    fn clear_title(&self) -> &'static str {
+        "SPRINT CLEAR"
+    }
+
+    fn results(&self, game: &Game) -> Vec<(&'static str, String)> {
+        vec![
+            ("Time", format_timer(game.get_time())),
+            ("Lines", game.lines.to_string()),
+            ("Score", game.score.to_string()),
+        ]
+    }
+
+    fn level(&self, rules: &Rules, _lines: u32) -> u8 {
+        rules.start_level
+    }
+
+    fn ranking(&self) -> Ranking {
+        Ranking::Time
+    }
+}
+
+/// Keeps the level it starts on, so the points come from the clears rather than the speed.
+pub struct Ultra;
+
+impl GameMode for Ultra {
+    fn is_goal_reached(&self, game: &Game) -> bool {
+        game.get_time() >= ULTRA_DURATION
+    }
+
+    fn timer(&self, game: &Game) -> Option<Duration> {
+        Some(ULTRA_DURATION.saturating_sub(game.get_time()))
+    }
+
+    fn hud(&self, game: &Game) -> [(&'static str, String); 3] {
+        [
+            ("Time", format_timer(ULTRA_DURATION.saturating_sub(game.get_time()))),
+            ("Score", game.score.to_string()),
+            ("Lines", game.lines.to_string()),
+        ]
+    }
+
+    fn clear_title(&self) -> &'static str {
+        "TIME UP"
+    }
+
+    fn results(&self, game: &Game) -> Vec<(&'static str, String)> {
+        vec![
+            ("Score", game.score.to_string()),
+            ("Lines", game.lines.to_string()),
+        ]
+    }
+
+    fn level(&self, rules: &Rules, _lines: u32) -> u8 {
+        rules.start_level
+    }
+}
+
+/// Starts with the bottom of the field full of garbage and refills it as rows are dug out,
+/// until the last of the garbage rows is cleared.
+pub struct Dig;
+
+impl GameMode for Dig {
+    fn is_goal_reached(&self, game: &Game) -> bool {
+        game.get_garbage_cleared() >= DIG_LINES
+    }
+
+    fn timer(&self, game: &Game) -> Option<Duration> {
+        Some(game.get_time())
+    }
+
+    fn hud(&self, game: &Game) -> [(&'static str, String); 3] {
+        [
+            ("Time", format_timer(game.get_time())),
+            ("Garbage", format!("{}/{}", game.get_garbage_cleared(), DIG_LINES)),
+            ("Score", game.score.to_string()),
+        ]
+    }
+
+    fn clear_title(&self) -> &'static str {
+        "DIG CLEAR"
+    }
+
+    fn results(&self, game: &Game) -> Vec<(&'static str, String)> {
+        vec![
+            ("Time", format_timer(game.get_time())),
+            ("Garbage", game.get_garbage_cleared().to_string()),
+            ("Lines", game.lines.to_string()),
+            ("Score", game.score.to_string()),
+        ]
+    }
+
+    fn level(&self, rules: &Rules, _lines: u32) -> u8 {
+        rules.start_level
+    }
+
+    fn garbage(&self, game: &Game) -> u16 {
+        let dealt = game.get_garbage_cleared() + game.get_garbage_rows() as u32;
+        let left = DIG_LINES.saturating_sub(dealt);
+        DIG_GARBAGE_HEIGHT
+            .min(game.field.height / 2)
+            .saturating_sub(game.get_garbage_rows())
+            .min(left.min(u16::MAX as u32) as u16)
+    }
+
+    fn ranking(&self) -> Ranking {
+        Ranking::Time
+    }
+}
+
+/// The game as it always was, going on until the stack tops out.
+pub struct Endless;
+
+impl GameMode for Endless {
+    fn is_goal_reached(&self, _game: &Game) -> bool {
+        false
+    }
+
+    fn hud(&self, game: &Game) -> [(&'static str, String); 3] {
+        [
+            ("Score", game.score.to_string()),
+            ("Level", game.level.to_string()),
+            ("Lines", game.lines.to_string()),
+        ]
+    }
+}
+
+/// Relaxed practice: the stack never tops out and the level never goes up, so the sidebar shows
+/// how fast and how cleanly pieces are placed instead of the score.
+pub struct Zen;
+
+impl GameMode for Zen {
+    fn is_goal_reached(&self, _game: &Game) -> bool {
+        false
+    }
+
+    fn hud(&self, game: &Game) -> [(&'static str, String); 3] {
+        [
+            ("Pieces", game.get_pieces().to_string()),
+            ("PPS", format!("{:.2}", game.get_pieces_per_second())),
+            ("Finesse", game.get_finesse_faults().to_string()),
+        ]
+    }
+
+    fn level(&self, rules: &Rules, _lines: u32) -> u8 {
+        rules.start_level
+    }
+
+    fn ends_on_top_out(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::bot::Bot;
+    use crate::game::consts::field::MIN_FIELD_HEIGHT;
+    use crate::game::events::GameEvent;
+    use crate::game::piece_set::PieceSet;
+    use crate::game::rendering::NullRenderer;
+    use crate::game::FRAME_DURATION;
+    use std::sync::mpsc;
+
+    fn new_game(mode: ModeKind) -> Game {
+        let rules = Rules {
+            mode,
+            ..Rules::default()
+        };
+        let mut game = Game::new(Box::new(NullRenderer), rules, 42);
+        game.set_paused(false);
+        game
+    }
+
+    #[test]
+    fn test_parse_kind() {
+        for kind in ModeKind::ALL {
+            assert_eq!(kind.name().parse::<ModeKind>(), Ok(kind));
+        }
+
+        assert!("puzzle".parse::<ModeKind>().is_err());
+    }
+
+    #[test]
+    fn test_format_timer() {
+        assert_eq!(format_timer(Duration::ZERO), "0:00.000");
+        assert_eq!(format_timer(Duration::from_millis(83_456)), "1:23.456");
+        assert_eq!(format_timer(ULTRA_DURATION), "3:00.000");
+    }
+
+    #[test]
+    fn test_sprint_keeps_level_and_ranks_by_time() {
+        let sprint = Sprint;
+        let rules = Rules::default();
+        assert_eq!(sprint.level(&rules, 39), rules.start_level);
+        assert_eq!(Marathon.level(&rules, 39), rules.start_level + 3);
+        assert_eq!(sprint.ranking(), Ranking::Time);
+        assert_eq!(Marathon.ranking(), Ranking::Score);
+    }
+
+    /// Lets the bot play until the game ends, giving an input every few frames.
+    fn play_out(game: &mut Game, frames_per_input: u64) {
+        let mut bot = Bot::default();
+        while !game.is_game_over() {
+            if game.get_frame().is_multiple_of(frames_per_input) {
+                game.apply(bot.next_input(game));
+            }
+            game.update(FRAME_DURATION);
+        }
+    }
+
+    #[test]
+    fn test_sprint_ends_after_forty_lines() {
+        let mut game = new_game(ModeKind::Sprint);
+        play_out(&mut game, 1);
+
+        assert!(game.is_cleared());
+        assert!(game.lines >= SPRINT_LINES);
+        assert_eq!(game.level, 1);
+        assert!(game.replay().verify().is_ok());
+    }
+
+    #[test]
+    fn test_ultra_ends_after_three_minutes() {
+        // Slowed down, so the bot does not stack up faster than it clears lines
+        let mut game = new_game(ModeKind::Ultra);
+        play_out(&mut game, 20);
+
+        assert!(game.is_cleared());
+        assert_eq!(game.get_time(), ULTRA_DURATION);
+        assert_eq!(game.get_mode().timer(&game), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_dig_starts_on_garbage() {
+        let game = new_game(ModeKind::Dig);
+
+        assert_eq!(game.get_garbage_rows(), DIG_GARBAGE_HEIGHT);
+        let height = game.field.rows() as i16;
+        for y in height - DIG_GARBAGE_HEIGHT as i16..height {
+            let holes = (0..10).filter(|&x| !game.field.is_occupied(x, y)).count();
+            assert_eq!(holes, 1);
+        }
+        assert!(!game.field.is_occupied(0, height - DIG_GARBAGE_HEIGHT as i16 - 1));
+    }
+
+    #[test]
+    fn test_dig_refills_cleared_garbage() {
+        let mut game = new_game(ModeKind::Dig);
+        let mut bot = Bot::default();
+        while game.get_garbage_cleared() < 5 {
+            assert!(!game.is_game_over());
+            game.apply(bot.next_input(&game));
+            game.update(FRAME_DURATION);
+        }
+
+        assert_eq!(game.get_garbage_rows(), DIG_GARBAGE_HEIGHT);
+        assert!(game.replay().verify().is_ok());
+    }
+
+    #[test]
+    fn test_dig_deals_no_more_garbage_than_the_goal() {
+        let mut game = new_game(ModeKind::Dig);
+        game.garbage_cleared = DIG_LINES - 3;
+        game.garbage_rows = 2;
+        assert_eq!(Dig.garbage(&game), 1);
+
+        game.garbage_rows = 3;
+        assert_eq!(Dig.garbage(&game), 0);
+        assert!(!Dig.is_goal_reached(&game));
+
+        game.garbage_cleared = DIG_LINES;
+        game.garbage_rows = 0;
+        assert!(Dig.is_goal_reached(&game));
+    }
+
+    #[test]
+    fn test_zen_clears_the_stack_instead_of_topping_out() {
+        let mut game = new_game(ModeKind::Zen);
+        let (sender, receiver) = mpsc::channel();
+        game.subscribe(Box::new(sender));
+
+        // Left alone, every piece lands on the last one right in the middle
+        game.update(Duration::from_secs(600));
+
+        assert!(!game.is_game_over());
+        assert_eq!(game.level, game.rules.start_level);
+        assert!(game.get_pieces() > 50);
+        assert!(receiver.try_iter().any(|event| event == GameEvent::StackCleared { rows: 10 }));
+    }
+
+    #[test]
+    fn test_zen_never_tops_out_on_a_small_field() {
+        for pieces in [PieceSet::standard(), PieceSet::builtin("pentomino").unwrap()] {
+            let rules = Rules {
+                mode: ModeKind::Zen,
+                pieces,
+                field_height: MIN_FIELD_HEIGHT,
+                field_buffer: 0,
+                ..Rules::default()
+            };
+            let mut game = Game::new(Box::new(NullRenderer), rules, 42);
+            game.set_paused(false);
+
+            let mut bot = Bot::default();
+            while game.get_pieces() < 200 {
+                assert!(!game.is_game_over(), "{} game topped out", game.rules.pieces);
+                game.apply(bot.next_input(&game));
+                game.update(FRAME_DURATION);
+            }
+        }
+    }
+
+    #[test]
+    fn test_endless_never_clears() {
+        let mut game = new_game(ModeKind::Endless);
+        while !game.is_game_over() {
+            game.update(Duration::from_secs(1));
+        }
+
+        assert!(!game.is_cleared());
+    }
+}
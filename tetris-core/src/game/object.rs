@@ -0,0 +1,228 @@
+use super::consts::kicks::Kick;
+use super::consts::qube::Qube;
+use super::geometry::{Direction, Pos};
+use super::piece_set::PieceSet;
+use super::tetromino::Tetromino;
+
+pub struct Object {
+    pub tetromino: Tetromino,
+    pub direction: Direction,
+    pub pos: Pos,
+    pub qube: Qube,
+    /// Width of the qube in its direction.
+    pub width: u16,
+}
+
+impl Object {
+    /// Creates an object of one of the pieces of the set.
+    pub fn new(pieces: &PieceSet, tetromino: Tetromino, direction: Direction, pos: Pos) -> Self {
+        let piece = &pieces[tetromino];
+        Object {
+            tetromino,
+            direction,
+            pos,
+            qube: piece.qube(&direction),
+            width: piece.dimensions(&direction).0,
+        }
+    }
+
+    pub fn get_position(&self) -> &Pos {
+        &self.pos
+    }
+
+    pub fn get_type(&self) -> &Tetromino {
+        &self.tetromino
+    }
+
+    pub fn get_direction(&self) -> &Direction {
+        &self.direction
+    }
+
+    pub fn get_qube(&self) -> &Qube {
+        &self.qube
+    }
+
+    pub fn get_qube_with_width(&self) -> (&Qube, u16) {
+        (self.get_qube(), self.width)
+    }
+
+    /// Positions to test when rotating into the given direction, one per wall kick, in SRS order.
+    /// Kicks that would push the qube out of the field on the left or top are skipped.
+    pub fn get_rotation_positions(
+        &self,
+        pieces: &PieceSet,
+        direction: &Direction,
+    ) -> Vec<(Kick, Pos)> {
+        let piece = &pieces[self.tetromino];
+        let (from_x, from_y) = piece.pivot_offset(&self.direction);
+        let (to_x, to_y) = piece.pivot_offset(direction);
+
+        piece
+            .kicks(&self.direction, direction)
+            .iter()
+            .filter_map(|&(kick_x, kick_y)| {
+                self.pos
+                    .copy_offset(
+                        to_x as i16 - from_x as i16 + kick_x as i16,
+                        to_y as i16 - from_y as i16 + kick_y as i16,
+                    )
+                    .map(|pos| ((kick_x, kick_y), pos))
+            })
+            .collect()
+    }
+
+    pub fn rotate_to(&mut self, pieces: &PieceSet, direction: Direction, pos: Pos) {
+        *self = Object::new(pieces, self.tetromino, direction, pos);
+    }
+
+    /// Rotates clockwise around the tetromino's center, without any wall kicks.
+    pub fn rotate(&mut self, pieces: &PieceSet) {
+        self.rotate_in_place(pieces, self.direction.copy_rotate());
+    }
+
+    /// Rotates counter-clockwise around the tetromino's center, without any wall kicks.
+    pub fn rotate_ccw(&mut self, pieces: &PieceSet) {
+        self.rotate_in_place(pieces, self.direction.copy_rotate_ccw());
+    }
+
+    /// Turns the tetromino upside down around its center, without any wall kicks.
+    pub fn rotate_180(&mut self, pieces: &PieceSet) {
+        self.rotate_in_place(pieces, self.direction.copy_rotate_180());
+    }
+
+    fn rotate_in_place(&mut self, pieces: &PieceSet, direction: Direction) {
+        let pos = self
+            .get_rotation_positions(pieces, &direction)
+            .first()
+            .map_or(self.pos, |&(_, pos)| pos);
+        self.rotate_to(pieces, direction, pos);
+    }
+}
+
+// Tests generated by Junie AI
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::geometry::Pos;
+    use crate::game::tetromino::Tetromino;
+    use crate::game::piece_set::PieceSet;
+
+    #[test]
+    fn test_new_object() {
+        let pieces = PieceSet::standard();
+        let tetromino = Tetromino::L;
+        let direction = Direction::Up;
+        let pos = Pos::zero();
+
+        let object = Object::new(&pieces, tetromino, direction, pos);
+
+        assert_eq!(*object.get_type(), tetromino);
+        assert_eq!(*object.get_direction(), direction);
+        assert_eq!(object.get_position().x, pos.x);
+        assert_eq!(object.get_position().y, pos.y);
+        assert_eq!(object.get_qube(), &pieces[tetromino].qube(&direction));
+    }
+
+    #[test]
+    fn test_get_qube_with_width() {
+        let pieces = PieceSet::standard();
+        let tetromino = Tetromino::L;
+        let direction = Direction::Up;
+        let pos = Pos::zero();
+
+        let object = Object::new(&pieces, tetromino, direction, pos);
+        let (qube, width) = object.get_qube_with_width();
+
+        assert_eq!(qube, &pieces[tetromino].qube(&direction));
+        assert_eq!(width, pieces[tetromino].dimensions(&direction).0);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let pieces = PieceSet::standard();
+        let tetromino = Tetromino::L;
+        let direction = Direction::Up;
+        let pos = Pos::zero();
+
+        let mut object = Object::new(&pieces, tetromino, direction, pos);
+        let original_qube = *object.get_qube();
+
+        object.rotate(&pieces);
+
+        assert_eq!(*object.get_direction(), Direction::Right);
+        assert_ne!(*object.get_qube(), original_qube);
+        assert_eq!(*object.get_qube(), pieces[tetromino].qube(&Direction::Right));
+    }
+
+    #[test]
+    fn test_rotate_around_center() {
+        let pieces = PieceSet::standard();
+        let mut object = Object::new(&pieces, Tetromino::T, Direction::Up, Pos::new(3, 0).unwrap());
+
+        // .T.    .T.
+        // TTT -> .TT
+        // ...    .T.
+        object.rotate(&pieces);
+        assert_eq!(object.get_position().x, 4);
+        assert_eq!(object.get_position().y, 0);
+
+        // Four rotations bring the tetromino back to where it started
+        object.rotate(&pieces);
+        object.rotate(&pieces);
+        object.rotate(&pieces);
+        assert_eq!(*object.get_direction(), Direction::Up);
+        assert_eq!(object.get_position().x, 3);
+        assert_eq!(object.get_position().y, 0);
+    }
+
+    #[test]
+    fn test_rotate_ccw_undoes_rotate() {
+        let pieces = PieceSet::standard();
+        for tetromino in Tetromino::ALL {
+            let pos = Pos::new(3, 5).unwrap();
+            let mut object = Object::new(&pieces, tetromino, Direction::Up, pos);
+
+            object.rotate(&pieces);
+            object.rotate_ccw(&pieces);
+            assert_eq!(*object.get_direction(), Direction::Up);
+            assert_eq!((object.get_position().x, object.get_position().y), (3, 5));
+        }
+    }
+
+    #[test]
+    fn test_rotate_180_around_center() {
+        let pieces = PieceSet::standard();
+        let mut object = Object::new(&pieces, Tetromino::T, Direction::Up, Pos::new(3, 0).unwrap());
+
+        // .T.    ...
+        // TTT -> TTT
+        // ...    .T.
+        object.rotate_180(&pieces);
+        assert_eq!(*object.get_direction(), Direction::Down);
+        assert_eq!((object.get_position().x, object.get_position().y), (3, 1));
+
+        object.rotate_180(&pieces);
+        assert_eq!(*object.get_direction(), Direction::Up);
+        assert_eq!((object.get_position().x, object.get_position().y), (3, 0));
+    }
+
+    #[test]
+    fn test_get_rotation_positions() {
+        let pieces = PieceSet::standard();
+        let object = Object::new(&pieces, Tetromino::I, Direction::Up, Pos::new(3, 5).unwrap());
+        let positions = object.get_rotation_positions(&pieces, &Direction::Right);
+
+        // One position per I kick test, the first one rotating in place
+        assert_eq!(positions.len(), 5);
+        assert_eq!(positions[0].0, (0, 0));
+        assert_eq!((positions[0].1.x, positions[0].1.y), (5, 4));
+        assert_eq!(positions[1].0, (-2, 0));
+        assert_eq!((positions[1].1.x, positions[1].1.y), (3, 4));
+
+        // Kicks leaving the field on the left are skipped
+        let object = Object::new(&pieces, Tetromino::I, Direction::Right, Pos::new(0, 5).unwrap());
+        let positions = object.get_rotation_positions(&pieces, &Direction::Up);
+        assert!(positions.iter().all(|(_, pos)| pos.x <= 2));
+        assert!(positions.len() < 5);
+    }
+}
@@ -0,0 +1,612 @@
+//! Piece sets: the shapes a game is played with, read from text instead of being written in code.
+//! See `pieces/tetromino.txt` for the format. Rotations are generated by turning the drawing.
+
+use super::consts::kicks::{Kick, NO_KICKS};
+use super::consts::qube::{Qube, QubeRow, QUBE_SIZE};
+use super::geometry::Direction;
+use super::tetromino::Tetromino;
+use rand::Rng;
+use std::fmt::{Debug, Display};
+use std::ops::Index;
+use std::sync::{Arc, OnceLock};
+
+/// Most pieces a set can hold.
+pub const MAX_PIECES: usize = u8::MAX as usize;
+
+/// The sets shipped with the game, by name.
+const BUILTIN_SETS: [(&str, &str); 3] = [
+    ("tetromino", include_str!("../../pieces/tetromino.txt")),
+    ("tromino", include_str!("../../pieces/tromino.txt")),
+    ("pentomino", include_str!("../../pieces/pentomino.txt")),
+];
+
+/// The built-in sets, read the first time they are asked for.
+static BUILTIN_CACHE: [OnceLock<Arc<PieceSet>>; 3] = [const { OnceLock::new() }; 3];
+
+/// A named kick table, its tests listed by the turn they are for, as direction indexes.
+type KickTable<'a> = (&'a str, Vec<(usize, usize, Vec<Kick>)>);
+
+/// Color a piece set gives one of its pieces. How it is shown is up to the frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Orange,
+    Grey,
+    Rgb(u8, u8, u8),
+}
+
+impl PieceColor {
+    const NAMED: [(&str, PieceColor); 9] = [
+        ("red", PieceColor::Red),
+        ("green", PieceColor::Green),
+        ("yellow", PieceColor::Yellow),
+        ("blue", PieceColor::Blue),
+        ("magenta", PieceColor::Magenta),
+        ("cyan", PieceColor::Cyan),
+        ("white", PieceColor::White),
+        ("orange", PieceColor::Orange),
+        ("grey", PieceColor::Grey),
+    ];
+
+    fn parse(text: &str) -> Option<PieceColor> {
+        if let Some(hex) = text.strip_prefix('#') {
+            if hex.len() != 6 || !hex.chars().all(|digit| digit.is_ascii_hexdigit()) {
+                return None;
+            }
+            let [_, r, g, b] = u32::from_str_radix(hex, 16).ok()?.to_be_bytes();
+            return Some(PieceColor::Rgb(r, g, b));
+        }
+        PieceColor::NAMED
+            .iter()
+            .find(|(name, _)| *name == text)
+            .map(|&(_, color)| color)
+    }
+}
+
+/// One direction of a piece: its cells trimmed to their bounding box, and where that box sits in
+/// the box the piece turns in.
+#[derive(Debug, Clone, Copy)]
+struct Rotation {
+    qube: Qube,
+    width: u16,
+    height: u16,
+    offset: (u16, u16),
+}
+
+#[derive(Debug)]
+pub struct Piece {
+    name: String,
+    color: PieceColor,
+    /// Side of the square box the piece turns in.
+    box_size: u16,
+    /// Every direction, by the number of clockwise turns from up.
+    rotations: [Rotation; 4],
+    /// Kick tests by the directions turned from and into, empty for rotations without kicks.
+    kicks: [[Vec<Kick>; 4]; 4],
+    spin: bool,
+    first: bool,
+}
+
+impl Piece {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn color(&self) -> PieceColor {
+        self.color
+    }
+
+    pub fn box_size(&self) -> u16 {
+        self.box_size
+    }
+
+    pub fn qube(&self, direction: &Direction) -> Qube {
+        self.rotations[turns(direction)].qube
+    }
+
+    pub fn dimensions(&self, direction: &Direction) -> (u16, u16) {
+        let rotation = &self.rotations[turns(direction)];
+        (rotation.width, rotation.height)
+    }
+
+    pub fn pivot_offset(&self, direction: &Direction) -> (u16, u16) {
+        self.rotations[turns(direction)].offset
+    }
+
+    pub fn kicks(&self, from: &Direction, to: &Direction) -> &[Kick] {
+        match self.kicks[turns(from)][turns(to)].as_slice() {
+            [] => &NO_KICKS,
+            kicks => kicks,
+        }
+    }
+
+    /// Whether rotating the piece into place is checked for spins, like the T tetromino is.
+    pub fn can_spin(&self) -> bool {
+        self.spin
+    }
+}
+
+/// The pieces a game deals, in the order they were defined in. Pieces are indexes into it.
+pub struct PieceSet {
+    name: String,
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+impl PieceSet {
+    /// Names of the sets shipped with the game.
+    pub const BUILTIN: [&str; 3] = [BUILTIN_SETS[0].0, BUILTIN_SETS[1].0, BUILTIN_SETS[2].0];
+
+    /// The seven tetrominoes, which games use unless their rules pick another set.
+    pub fn standard() -> Arc<PieceSet> {
+        PieceSet::builtin(BUILTIN_SETS[0].0).expect("The standard piece set is built in")
+    }
+
+    /// One of the sets shipped with the game, by name.
+    pub fn builtin(name: &str) -> Option<Arc<PieceSet>> {
+        let idx = BUILTIN_SETS.iter().position(|&(builtin, _)| builtin == name)?;
+        let set = BUILTIN_CACHE[idx].get_or_init(|| {
+            let (name, source) = BUILTIN_SETS[idx];
+            Arc::new(PieceSet::parse(name, source).expect("Built-in piece sets are valid"))
+        });
+        Some(Arc::clone(set))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The text the set was read from.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether this is one of the sets shipped with the game, unchanged.
+    pub fn is_builtin(&self) -> bool {
+        BUILTIN_SETS.contains(&(self.name.as_str(), self.source.as_str()))
+    }
+
+    /// How many pieces the set holds, never none.
+    pub fn get_count(&self) -> usize {
+        self.pieces.len()
+    }
+
+    /// The piece with the given index, in the order of the set.
+    pub fn get(&self, index: u8) -> Option<Tetromino> {
+        ((index as usize) < self.pieces.len()).then(|| Tetromino::new(index))
+    }
+
+    pub fn tetrominoes(&self) -> impl Iterator<Item = Tetromino> + use<> {
+        (0..self.pieces.len() as u8).map(Tetromino::new)
+    }
+
+    pub fn find(&self, name: &str) -> Option<Tetromino> {
+        let index = self.pieces.iter().position(|piece| piece.name == name)?;
+        self.get(index as u8)
+    }
+
+    /// The pieces the tgm randomizer may deal first, all of them unless some are marked so.
+    pub fn first_tetrominoes(&self) -> Vec<Tetromino> {
+        let first: Vec<Tetromino> = self.tetrominoes().filter(|&t| self[t].first).collect();
+        if first.is_empty() {
+            self.tetrominoes().collect()
+        } else {
+            first
+        }
+    }
+
+    pub fn random(&self, rng: &mut impl Rng) -> Tetromino {
+        Tetromino::new(rng.random_range(0..self.pieces.len()) as u8)
+    }
+
+    /// Cells the largest piece spans in any direction, the narrowest the field can be.
+    pub fn get_max_size(&self) -> u16 {
+        self.pieces
+            .iter()
+            .flat_map(|piece| piece.rotations.iter())
+            .map(|rotation| rotation.width.max(rotation.height))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Reads a piece set from its text. Errors tell the line at fault.
+    pub fn parse(name: &str, source: &str) -> Result<PieceSet, String> {
+        let mut kick_tables: Vec<KickTable> = Vec::new();
+        let mut headers: Vec<(usize, &str)> = Vec::new();
+        let mut grids: Vec<Vec<&str>> = Vec::new();
+        let mut in_piece = false;
+
+        for (number, line) in source.lines().enumerate() {
+            let at = |error: String| format!("Line {}: {}", number + 1, error);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("kicks") => {
+                    let table = words.next().ok_or_else(|| at("Kick table has no name".into()))?;
+                    if kick_tables.iter().any(|(other, _)| *other == table) {
+                        return Err(at(format!("Kick table '{}' is defined twice", table)));
+                    }
+                    kick_tables.push((table, Vec::new()));
+                    in_piece = false;
+                }
+                Some("piece") => {
+                    headers.push((number + 1, line));
+                    grids.push(Vec::new());
+                    in_piece = true;
+                }
+                Some(_) if in_piece => grids.last_mut().expect("A piece was started").push(line),
+                Some(rotation) => {
+                    let (_, rows) = kick_tables
+                        .last_mut()
+                        .ok_or_else(|| at(format!("Unexpected '{}'", line)))?;
+                    rows.push(parse_kick_row(rotation, words).map_err(at)?);
+                }
+                None => unreachable!("Blank lines are skipped"),
+            }
+        }
+
+        let pieces = headers
+            .iter()
+            .zip(grids)
+            .map(|(&(number, header), grid)| {
+                parse_piece(header, &grid, &kick_tables)
+                    .map_err(|error| format!("Line {}: {}", number, error))
+            })
+            .collect::<Result<Vec<Piece>, String>>()?;
+
+        if pieces.is_empty() {
+            return Err("Piece set has no pieces".to_string());
+        }
+        if pieces.len() > MAX_PIECES {
+            return Err(format!("Piece set has more than {} pieces", MAX_PIECES));
+        }
+        for (idx, piece) in pieces.iter().enumerate() {
+            if pieces[..idx].iter().any(|other| other.name == piece.name) {
+                return Err(format!("Piece '{}' is defined twice", piece.name));
+            }
+        }
+
+        Ok(PieceSet {
+            name: name.to_string(),
+            source: source.to_string(),
+            pieces,
+        })
+    }
+}
+
+impl Index<Tetromino> for PieceSet {
+    type Output = Piece;
+
+    fn index(&self, tetromino: Tetromino) -> &Piece {
+        &self.pieces[tetromino.index() as usize]
+    }
+}
+
+impl PartialEq for PieceSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.source == other.source
+    }
+}
+
+impl Eq for PieceSet {}
+
+impl Debug for PieceSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PieceSet({})", self.name)
+    }
+}
+
+impl Display for PieceSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Index of the direction in the tables of a piece, as clockwise turns from up.
+fn turns(direction: &Direction) -> usize {
+    match direction {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+fn parse_direction(name: &str) -> Result<usize, String> {
+    match name {
+        "up" => Ok(0),
+        "right" => Ok(1),
+        "down" => Ok(2),
+        "left" => Ok(3),
+        _ => Err(format!("Unknown direction '{}', expected up, right, down or left", name)),
+    }
+}
+
+/// Reads a line of a kick table, such as "up>right 0,0 -1,0".
+fn parse_kick_row<'a>(
+    rotation: &str,
+    offsets: impl Iterator<Item = &'a str>,
+) -> Result<(usize, usize, Vec<Kick>), String> {
+    let (from, to) = rotation
+        .split_once('>')
+        .ok_or(format!("Expected a rotation such as up>right, found '{}'", rotation))?;
+    let (from, to) = (parse_direction(from)?, parse_direction(to)?);
+    if from == to {
+        return Err(format!("Rotation '{}' does not turn", rotation));
+    }
+
+    let kicks = offsets
+        .map(|offset| {
+            offset
+                .split_once(',')
+                .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                .ok_or(format!("Expected an offset such as -1,2, found '{}'", offset))
+        })
+        .collect::<Result<Vec<Kick>, String>>()?;
+    if kicks.is_empty() {
+        return Err(format!("Rotation '{}' has no offsets", rotation));
+    }
+    Ok((from, to, kicks))
+}
+
+/// Reads a piece from its header line and the lines drawing it.
+fn parse_piece(
+    header: &str,
+    grid: &[&str],
+    kick_tables: &[KickTable],
+) -> Result<Piece, String> {
+    let mut words = header.split_whitespace().skip(1);
+    let name = words.next().ok_or("Piece has no name")?;
+    let color = words.next().ok_or(format!("Piece '{}' has no color", name))?;
+    let color = PieceColor::parse(color).ok_or(format!(
+        "Unknown color '{}', expected red, green, yellow, blue, magenta, cyan, white, orange, \
+         grey or #rrggbb",
+        color
+    ))?;
+
+    let mut piece = Piece {
+        name: name.to_string(),
+        color,
+        box_size: grid.len() as u16,
+        rotations: [Rotation {
+            qube: [0; QUBE_SIZE as usize],
+            width: 0,
+            height: 0,
+            offset: (0, 0),
+        }; 4],
+        kicks: Default::default(),
+        spin: false,
+        first: false,
+    };
+
+    for word in words {
+        match word.split_once('=') {
+            Some(("kicks", tables)) => {
+                for table in tables.split(',') {
+                    let (_, rows) = kick_tables
+                        .iter()
+                        .find(|(other, _)| *other == table)
+                        .ok_or(format!("Unknown kick table '{}'", table))?;
+                    for (from, to, kicks) in rows {
+                        if !piece.kicks[*from][*to].is_empty() {
+                            return Err(format!("Piece '{}' has two kick tests per rotation", name));
+                        }
+                        piece.kicks[*from][*to] = kicks.clone();
+                    }
+                }
+            }
+            _ if word == "spin" => piece.spin = true,
+            _ if word == "first" => piece.first = true,
+            _ => return Err(format!("Unknown piece option '{}'", word)),
+        }
+    }
+
+    let cells = parse_grid(name, grid)?;
+    let mut cells_turned = cells.clone();
+    for rotation in piece.rotations.iter_mut() {
+        *rotation = trim(&cells_turned);
+        cells_turned = turn_clockwise(&cells_turned);
+    }
+    Ok(piece)
+}
+
+/// Reads the drawing of a piece into its cells, row by row.
+fn parse_grid(name: &str, grid: &[&str]) -> Result<Vec<Vec<bool>>, String> {
+    let size = grid.len();
+    if size == 0 {
+        return Err(format!("Piece '{}' is not drawn", name));
+    }
+    let not_square = || {
+        format!("Piece '{}' has to be drawn in a square of up to {} cells", name, QUBE_SIZE)
+    };
+    if size > QUBE_SIZE as usize {
+        return Err(not_square());
+    }
+
+    let cells = grid
+        .iter()
+        .map(|line| {
+            line.chars()
+                .map(|cell| match cell {
+                    'X' => Ok(true),
+                    '.' => Ok(false),
+                    _ => Err(format!("Piece '{}' has the cell '{}', expected X or .", name, cell)),
+                })
+                .collect::<Result<Vec<bool>, String>>()
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if cells.iter().any(|row| row.len() != size) {
+        return Err(not_square());
+    }
+    if !cells.iter().flatten().any(|&cell| cell) {
+        return Err(format!("Piece '{}' has no cells", name));
+    }
+    Ok(cells)
+}
+
+fn turn_clockwise(cells: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    let size = cells.len();
+    (0..size)
+        .map(|y| (0..size).map(|x| cells[size - 1 - x][y]).collect())
+        .collect()
+}
+
+/// Cuts the cells down to the rows and columns holding any, keeping where they started.
+fn trim(cells: &[Vec<bool>]) -> Rotation {
+    let filled = |x: usize, y: usize| cells[y][x];
+    let size = cells.len();
+    let rows: Vec<usize> = (0..size).filter(|&y| (0..size).any(|x| filled(x, y))).collect();
+    let columns: Vec<usize> = (0..size).filter(|&x| (0..size).any(|y| filled(x, y))).collect();
+    let (left, top) = (columns[0], rows[0]);
+    let (width, height) = (columns[columns.len() - 1] - left + 1, rows[rows.len() - 1] - top + 1);
+
+    let mut qube = [0; QUBE_SIZE as usize];
+    for (y, row) in qube.iter_mut().enumerate().take(height) {
+        for x in 0..width {
+            if filled(left + x, top + y) {
+                *row |= 1 << (QubeRow::BITS as usize - 1 - x);
+            }
+        }
+    }
+
+    Rotation {
+        qube,
+        width: width as u16,
+        height: height as u16,
+        offset: (left as u16, top as u16),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIRECTIONS: [Direction; 4] =
+        [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    fn cells(qube: &Qube) -> u32 {
+        qube.iter().map(|row| row.count_ones()).sum()
+    }
+
+    #[test]
+    fn test_standard_set_turns_like_srs() {
+        let pieces = PieceSet::standard();
+        let t = &pieces[pieces.find("T").unwrap()];
+        assert_eq!(pieces.find("T"), Some(Tetromino::T));
+        assert!(t.can_spin());
+        assert_eq!(t.qube(&Direction::Up)[..3], [0b0100_0000, 0b1110_0000, 0]);
+        assert_eq!(t.qube(&Direction::Right)[..3], [0b1000_0000, 0b1100_0000, 0b1000_0000]);
+        assert_eq!(t.pivot_offset(&Direction::Right), (1, 0));
+        assert_eq!(t.pivot_offset(&Direction::Down), (0, 1));
+
+        let i = &pieces[Tetromino::I];
+        assert_eq!(i.dimensions(&Direction::Up), (4, 1));
+        assert_eq!(i.dimensions(&Direction::Left), (1, 4));
+        assert_eq!(i.pivot_offset(&Direction::Right), (2, 0));
+        assert_eq!(pieces[Tetromino::O].kicks(&Direction::Up, &Direction::Down), &NO_KICKS);
+        assert_eq!(pieces.get_max_size(), 4);
+    }
+
+    #[test]
+    fn test_kicks_start_in_place_and_are_reversible() {
+        let pieces = PieceSet::standard();
+        for tetromino in Tetromino::ALL {
+            let piece = &pieces[tetromino];
+            for from in DIRECTIONS {
+                for to in DIRECTIONS.into_iter().filter(|&to| to != from) {
+                    let kicks = piece.kicks(&from, &to);
+                    assert_eq!(kicks[0], (0, 0));
+
+                    if to != from.copy_rotate_180() {
+                        let back: Vec<Kick> =
+                            piece.kicks(&to, &from).iter().map(|&(x, y)| (-x, -y)).collect();
+                        assert_eq!(kicks, back, "{} from {:?} to {:?}", piece.name(), from, to);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_builtin_sets_keep_their_cells_in_every_direction() {
+        for (name, count, size) in [("tetromino", 7, 4), ("tromino", 2, 3), ("pentomino", 18, 5)] {
+            let set = PieceSet::builtin(name).unwrap();
+            assert!(set.is_builtin());
+            assert_eq!(set.get_count(), count, "{}", name);
+            for tetromino in set.tetrominoes() {
+                let piece = &set[tetromino];
+                for direction in DIRECTIONS {
+                    let qube = piece.qube(&direction);
+                    assert_eq!(cells(&qube), size as u32, "{} {:?}", piece.name(), direction);
+                }
+            }
+        }
+        assert_eq!(PieceSet::builtin("pentomino").unwrap().get_max_size(), 5);
+        assert!(PieceSet::builtin("hexomino").is_none());
+    }
+
+    #[test]
+    fn test_sets_are_told_apart_by_their_text() {
+        let source = "piece corner blue\nX.\nXX\n";
+        let set = PieceSet::parse("corner", source).unwrap();
+        assert_eq!(set, PieceSet::parse("corner", source).unwrap());
+        assert_ne!(set, PieceSet::parse("corner", "piece corner red\nX.\nXX\n").unwrap());
+        assert!(!set.is_builtin());
+        assert_eq!(set.get(0), Some(Tetromino::L));
+        assert_eq!(set.get(1), None);
+
+        // Built-in sets are read once and shared
+        assert!(Arc::ptr_eq(&PieceSet::builtin("tetromino").unwrap(), &PieceSet::standard()));
+    }
+
+    #[test]
+    fn test_custom_piece_turns_in_its_box() {
+        let source = "kicks wide\nup>right 0,0 -3,0\n\npiece hook #ff8000 kicks=wide spin first\n\
+                      X...\nXXXX\n....\n....\n";
+        let pieces = PieceSet::parse("hook", source).unwrap();
+        let hook = &pieces[pieces.find("hook").unwrap()];
+        assert_eq!(hook.color(), PieceColor::Rgb(255, 128, 0));
+        assert!(hook.can_spin());
+        assert_eq!(hook.box_size(), 4);
+        assert_eq!(hook.dimensions(&Direction::Right), (2, 4));
+        assert_eq!(hook.pivot_offset(&Direction::Right), (2, 0));
+        assert_eq!(hook.pivot_offset(&Direction::Down), (0, 2));
+        assert_eq!(hook.kicks(&Direction::Up, &Direction::Right), &[(0, 0), (-3, 0)]);
+        assert_eq!(hook.kicks(&Direction::Right, &Direction::Up), &NO_KICKS);
+    }
+
+    #[test]
+    fn test_errors_tell_the_line() {
+        for (source, error) in [
+            ("", "Piece set has no pieces"),
+            ("piece A pink\nX\n", "Line 1: Unknown color 'pink'"),
+            ("piece A red\nXX\n", "Line 1: Piece 'A' has to be drawn in a square"),
+            ("piece A red\n..\n..\n", "Line 1: Piece 'A' has no cells"),
+            ("piece A red\nXO\nXX\n", "Line 1: Piece 'A' has the cell 'O'"),
+            ("piece A red kicks=srs\nX\n", "Line 1: Unknown kick table 'srs'"),
+            ("piece A red\nX\npiece A red\nX\n", "Piece 'A' is defined twice"),
+            ("up>right 0,0\n", "Line 1: Unexpected 'up>right 0,0'"),
+            ("kicks a\nup>up 0,0\n", "Line 2: Rotation 'up>up' does not turn"),
+            ("kicks a\nup>right 0;0\n", "Line 2: Expected an offset such as -1,2, found '0;0'"),
+        ] {
+            let result = PieceSet::parse("broken", source);
+            assert!(
+                result.is_err_and(|message| message.starts_with(error)),
+                "{:?} should fail with {:?}",
+                source,
+                error
+            );
+        }
+    }
+}
@@ -0,0 +1,551 @@
+use super::piece_set::PieceSet;
+use super::tetromino::Tetromino;
+use super::GameRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Strategy deciding which tetromino is dealt next.
+/// All randomness has to come from the passed game RNG to keep seeded games reproducible.
+pub trait Randomizer: Send + Sync {
+    fn next_tetromino(&mut self, rng: &mut GameRng) -> Tetromino;
+
+    /// Forgets everything dealt so far, as if the randomizer was just created.
+    fn reset(&mut self);
+
+    /// What the randomizer remembers of the tetrominoes dealt so far, for saving a game.
+    fn save_state(&self) -> Vec<u8>;
+
+    /// Picks up from a state returned by `save_state`, failing if it does not fit this randomizer.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandomizerKind {
+    /// Raises the chance of every tetromino the longer it has not been dealt.
+    Weighted,
+    /// Deals every piece of the set in random order before starting over. Named after the seven
+    /// tetrominoes of the standard set.
+    SevenBag,
+    /// Like the seven bag, but with two of each piece per bag.
+    FourteenBag,
+    /// Every tetromino has the same chance, every time.
+    Uniform,
+    /// Rerolls once when the same tetromino would be dealt twice in a row, like the NES version did.
+    Nes,
+    /// Rerolls up to four times to avoid any of the last four tetrominoes, like TGM did.
+    Tgm,
+}
+
+impl RandomizerKind {
+    pub const ALL: [RandomizerKind; 6] = [
+        RandomizerKind::Weighted,
+        RandomizerKind::SevenBag,
+        RandomizerKind::FourteenBag,
+        RandomizerKind::Uniform,
+        RandomizerKind::Nes,
+        RandomizerKind::Tgm,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RandomizerKind::Weighted => "weighted",
+            RandomizerKind::SevenBag => "7-bag",
+            RandomizerKind::FourteenBag => "14-bag",
+            RandomizerKind::Uniform => "random",
+            RandomizerKind::Nes => "nes",
+            RandomizerKind::Tgm => "tgm",
+        }
+    }
+
+    /// Creates the randomizer, dealing the pieces of the given set.
+    pub fn create(&self, pieces: &Arc<PieceSet>) -> Box<dyn Randomizer> {
+        let pieces = Arc::clone(pieces);
+        match self {
+            RandomizerKind::Weighted => Box::new(WeightedRandomizer::new(pieces)),
+            RandomizerKind::SevenBag => Box::new(BagRandomizer::new(pieces, 1)),
+            RandomizerKind::FourteenBag => Box::new(BagRandomizer::new(pieces, 2)),
+            RandomizerKind::Uniform => Box::new(UniformRandomizer::new(pieces)),
+            RandomizerKind::Nes => Box::new(NesRandomizer::new(pieces)),
+            RandomizerKind::Tgm => Box::new(HistoryRandomizer::new(pieces)),
+        }
+    }
+}
+
+impl Display for RandomizerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for RandomizerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RandomizerKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = RandomizerKind::ALL.iter().map(|kind| kind.name()).collect();
+                format!("Unknown randomizer '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
+
+/// Reads a tetromino written as its index, refusing bytes that are no index.
+fn tetromino_from_byte(pieces: &PieceSet, byte: u8) -> Result<Tetromino, String> {
+    pieces
+        .get(byte)
+        .ok_or_else(|| format!("Unknown tetromino {} in the randomizer state", byte))
+}
+
+pub struct WeightedRandomizer {
+    pieces: Arc<PieceSet>,
+    /// Weight of every piece, in the order of the set.
+    weights: Vec<u32>,
+}
+
+impl WeightedRandomizer {
+    pub fn new(pieces: Arc<PieceSet>) -> Self {
+        WeightedRandomizer {
+            weights: vec![1; pieces.get_count()],
+            pieces,
+        }
+    }
+
+    pub fn get_next_tetromino(&self, rng: &mut impl Rng) -> Tetromino {
+        let total_weight: u32 = self.weights.iter().sum();
+        let mut random_value = rng.random_range(0..total_weight);
+
+        for (idx, &weight) in self.weights.iter().enumerate() {
+            if random_value < weight {
+                return self.pieces.get(idx as u8).expect("There is a weight per piece");
+            }
+            random_value -= weight;
+        }
+        unreachable!("Failed to select a tetromino from the bag.");
+    }
+
+    pub fn update_weights(&mut self, last_drawn_tetromino: &Tetromino) {
+        let last_drawn_idx = last_drawn_tetromino.index() as usize;
+
+        for i in 0..self.weights.len() {
+            if i == last_drawn_idx {
+                self.weights[i] = 1;
+            } else {
+                self.weights[i] += 1;
+            }
+        }
+    }
+}
+
+impl Default for WeightedRandomizer {
+    fn default() -> Self {
+        Self::new(PieceSet::standard())
+    }
+}
+
+impl Randomizer for WeightedRandomizer {
+    fn next_tetromino(&mut self, rng: &mut GameRng) -> Tetromino {
+        let next_tetromino = self.get_next_tetromino(rng);
+        self.update_weights(&next_tetromino);
+        next_tetromino
+    }
+
+    fn reset(&mut self) {
+        self.weights.fill(1);
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        self.weights.iter().flat_map(|weight| weight.to_le_bytes()).collect()
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        if state.len() != self.weights.len() * 4 {
+            return Err("Invalid weights in the randomizer state".to_string());
+        }
+
+        let weights: Vec<u32> = state
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        // Exactly one tetromino was dealt last, unless nothing was dealt yet
+        let last_dealt = weights.iter().filter(|&&weight| weight == 1).count();
+        if weights.contains(&0) || last_dealt == 0 || (last_dealt > 1 && last_dealt < weights.len())
+        {
+            return Err("Invalid weights in the randomizer state".to_string());
+        }
+
+        self.weights = weights;
+        Ok(())
+    }
+}
+
+pub struct BagRandomizer {
+    pieces: Arc<PieceSet>,
+    /// How many of every piece go into one bag.
+    sets: usize,
+    bag: Vec<Tetromino>,
+}
+
+impl BagRandomizer {
+    pub fn new(pieces: Arc<PieceSet>, sets: usize) -> Self {
+        BagRandomizer {
+            bag: Vec::with_capacity(sets * pieces.get_count()),
+            pieces,
+            sets,
+        }
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next_tetromino(&mut self, rng: &mut GameRng) -> Tetromino {
+        if self.bag.is_empty() {
+            for _ in 0..self.sets {
+                self.bag.extend(self.pieces.tetrominoes());
+            }
+            self.bag.shuffle(rng);
+        }
+
+        self.bag.pop().expect("The bag is refilled when empty")
+    }
+
+    fn reset(&mut self) {
+        self.bag.clear();
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        self.bag.iter().map(|tetromino| tetromino.index()).collect()
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        let bag = state
+            .iter()
+            .map(|&byte| tetromino_from_byte(&self.pieces, byte))
+            .collect::<Result<Vec<_>, _>>()?;
+        let overfull = self
+            .pieces
+            .tetrominoes()
+            .any(|tetromino| bag.iter().filter(|&&t| t == tetromino).count() > self.sets);
+        if overfull {
+            return Err("The bag holds more tetrominoes than it was filled with".to_string());
+        }
+
+        self.bag = bag;
+        Ok(())
+    }
+}
+
+pub struct UniformRandomizer {
+    pieces: Arc<PieceSet>,
+}
+
+impl UniformRandomizer {
+    pub fn new(pieces: Arc<PieceSet>) -> Self {
+        UniformRandomizer { pieces }
+    }
+}
+
+impl Randomizer for UniformRandomizer {
+    fn next_tetromino(&mut self, rng: &mut GameRng) -> Tetromino {
+        self.pieces.random(rng)
+    }
+
+    fn reset(&mut self) {}
+
+    fn save_state(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        if state.is_empty() {
+            Ok(())
+        } else {
+            Err("Unexpected state for a randomizer without memory".to_string())
+        }
+    }
+}
+
+pub struct NesRandomizer {
+    pieces: Arc<PieceSet>,
+    last: Option<Tetromino>,
+}
+
+impl NesRandomizer {
+    pub fn new(pieces: Arc<PieceSet>) -> Self {
+        NesRandomizer { pieces, last: None }
+    }
+}
+
+impl Default for NesRandomizer {
+    fn default() -> Self {
+        Self::new(PieceSet::standard())
+    }
+}
+
+impl Randomizer for NesRandomizer {
+    fn next_tetromino(&mut self, rng: &mut GameRng) -> Tetromino {
+        // The NES rolls one number too many, which also triggers the reroll
+        let roll = rng.random_range(0..=self.pieces.get_count());
+        let tetromino = match self.pieces.get(roll as u8) {
+            Some(tetromino) if Some(tetromino) != self.last => tetromino,
+            _ => self.pieces.random(rng),
+        };
+
+        self.last = Some(tetromino);
+        tetromino
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        self.last
+            .map(|tetromino| tetromino.index())
+            .into_iter()
+            .collect()
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        self.last = match state {
+            [] => None,
+            [byte] => Some(tetromino_from_byte(&self.pieces, *byte)?),
+            _ => return Err("Invalid last tetromino in the randomizer state".to_string()),
+        };
+        Ok(())
+    }
+}
+
+const HISTORY_SIZE: usize = 4;
+const HISTORY_ROLLS: usize = 4;
+
+pub struct HistoryRandomizer {
+    pieces: Arc<PieceSet>,
+    /// The first tetromino is never one that would force an overhang, as the set marks them
+    first_tetrominoes: Vec<Tetromino>,
+    history: [Tetromino; HISTORY_SIZE],
+    first: bool,
+}
+
+impl HistoryRandomizer {
+    pub fn new(pieces: Arc<PieceSet>) -> Self {
+        HistoryRandomizer {
+            first_tetrominoes: pieces.first_tetrominoes(),
+            history: Self::initial_history(&pieces),
+            pieces,
+            first: true,
+        }
+    }
+
+    /// The history starts out full of the last piece of the set,
+    /// the Z tetromino in the standard one.
+    fn initial_history(pieces: &PieceSet) -> [Tetromino; HISTORY_SIZE] {
+        let last = pieces.get_count() - 1;
+        [pieces.get(last as u8).expect("Sets are never empty"); HISTORY_SIZE]
+    }
+}
+
+impl Default for HistoryRandomizer {
+    fn default() -> Self {
+        Self::new(PieceSet::standard())
+    }
+}
+
+impl Randomizer for HistoryRandomizer {
+    fn next_tetromino(&mut self, rng: &mut GameRng) -> Tetromino {
+        let tetromino = if self.first {
+            self.first = false;
+            self.first_tetrominoes[rng.random_range(0..self.first_tetrominoes.len())]
+        } else {
+            let mut tetromino = self.pieces.random(rng);
+            for _ in 1..HISTORY_ROLLS {
+                if !self.history.contains(&tetromino) {
+                    break;
+                }
+                tetromino = self.pieces.random(rng);
+            }
+            tetromino
+        };
+
+        self.history.rotate_right(1);
+        self.history[0] = tetromino;
+        tetromino
+    }
+
+    fn reset(&mut self) {
+        self.history = Self::initial_history(&self.pieces);
+        self.first = true;
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        let mut state = vec![self.first as u8];
+        state.extend(self.history.iter().map(|tetromino| tetromino.index()));
+        state
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), String> {
+        let [first @ (0 | 1), history @ ..] = state else {
+            return Err("Invalid history in the randomizer state".to_string());
+        };
+        if history.len() != HISTORY_SIZE {
+            return Err("Invalid history in the randomizer state".to_string());
+        }
+
+        for (slot, &byte) in self.history.iter_mut().zip(history) {
+            *slot = tetromino_from_byte(&self.pieces, byte)?;
+        }
+        self.first = *first == 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn deal(randomizer: &mut dyn Randomizer, count: usize) -> Vec<Tetromino> {
+        let mut rng = GameRng::seed_from_u64(42);
+        (0..count).map(|_| randomizer.next_tetromino(&mut rng)).collect()
+    }
+
+    fn count_of(tetrominoes: &[Tetromino], tetromino: Tetromino) -> usize {
+        tetrominoes.iter().filter(|&&t| t == tetromino).count()
+    }
+
+    #[test]
+    fn test_parse_kind() {
+        for kind in RandomizerKind::ALL {
+            assert_eq!(kind.name().parse::<RandomizerKind>(), Ok(kind));
+        }
+
+        assert!("8-bag".parse::<RandomizerKind>().is_err());
+    }
+
+    #[test]
+    fn test_seven_bag() {
+        let dealt = deal(&mut BagRandomizer::new(PieceSet::standard(), 1), 70);
+
+        for bag in dealt.chunks(7) {
+            for tetromino in Tetromino::ALL {
+                assert_eq!(count_of(bag, tetromino), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fourteen_bag() {
+        let dealt = deal(&mut BagRandomizer::new(PieceSet::standard(), 2), 70);
+
+        for bag in dealt.chunks(14) {
+            for tetromino in Tetromino::ALL {
+                assert_eq!(count_of(bag, tetromino), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_resets_dealt_weight() {
+        let mut randomizer = WeightedRandomizer::default();
+        let dealt = deal(&mut randomizer, 1)[0];
+
+        assert_eq!(randomizer.weights[dealt.index() as usize], 1);
+        assert_eq!(randomizer.weights.iter().sum::<u32>(), 1 + 6 * 2);
+
+        randomizer.reset();
+        assert_eq!(randomizer.weights, vec![1; 7]);
+    }
+
+    #[test]
+    fn test_nes_reduces_repeats() {
+        let repeats = |dealt: &[Tetromino]| dealt.windows(2).filter(|w| w[0] == w[1]).count();
+
+        let nes = deal(&mut NesRandomizer::default(), 7000);
+        let uniform = deal(&mut UniformRandomizer::new(PieceSet::standard()), 7000);
+
+        // Uniform repeats about one in seven times, the reroll brings that down to about one in 28
+        assert!(repeats(&nes) < repeats(&uniform) / 2);
+    }
+
+    #[test]
+    fn test_tgm_history() {
+        let mut randomizer = HistoryRandomizer::default();
+        let mut rng = GameRng::seed_from_u64(42);
+        let first = [Tetromino::L, Tetromino::J, Tetromino::T, Tetromino::I];
+        assert_eq!(randomizer.first_tetrominoes, first);
+
+        for _ in 0..100 {
+            randomizer.reset();
+            assert!(first.contains(&randomizer.next_tetromino(&mut rng)));
+        }
+
+        let dealt = deal(&mut randomizer, 7000);
+        let repeats = dealt.windows(2).filter(|w| w[0] == w[1]).count();
+        assert!(repeats < 7000 / 28);
+    }
+
+    #[test]
+    fn test_loaded_state_deals_the_same() {
+        for kind in RandomizerKind::ALL {
+            let mut rng = GameRng::seed_from_u64(7);
+            let mut randomizer = kind.create(&PieceSet::standard());
+            for _ in 0..10 {
+                randomizer.next_tetromino(&mut rng);
+            }
+
+            let mut loaded = kind.create(&PieceSet::standard());
+            loaded.load_state(&randomizer.save_state()).unwrap();
+            let mut loaded_rng = rng.clone();
+            for _ in 0..30 {
+                assert_eq!(
+                    loaded.next_tetromino(&mut loaded_rng),
+                    randomizer.next_tetromino(&mut rng),
+                    "{} randomizer",
+                    kind
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_state() {
+        assert!(WeightedRandomizer::default().load_state(&[1, 2, 3]).is_err());
+        assert!(BagRandomizer::new(PieceSet::standard(), 1).load_state(&[0, 0]).is_err());
+        assert!(BagRandomizer::new(PieceSet::standard(), 2).load_state(&[0, 0]).is_ok());
+        assert!(NesRandomizer::default().load_state(&[7]).is_err());
+        assert!(HistoryRandomizer::default().load_state(&[2, 0, 0, 0, 0]).is_err());
+        assert!(UniformRandomizer::new(PieceSet::standard()).load_state(&[0]).is_err());
+    }
+
+    #[test]
+    fn test_every_kind_deals_every_tetromino() {
+        for kind in RandomizerKind::ALL {
+            let dealt = deal(kind.create(&PieceSet::standard()).as_mut(), 700);
+
+            for tetromino in Tetromino::ALL {
+                assert!(count_of(&dealt, tetromino) > 0, "{} never dealt {:?}", kind, tetromino);
+            }
+        }
+    }
+
+    #[test]
+    fn test_deals_the_pieces_of_any_set() {
+        let pentominoes = PieceSet::builtin("pentomino").unwrap();
+        let dealt = deal(&mut BagRandomizer::new(pentominoes.clone(), 1), 36);
+        for bag in dealt.chunks(18) {
+            for tetromino in pentominoes.tetrominoes() {
+                assert_eq!(count_of(bag, tetromino), 1);
+            }
+        }
+
+        for kind in RandomizerKind::ALL {
+            let dealt = deal(kind.create(&pentominoes).as_mut(), 1800);
+            for tetromino in pentominoes.tetrominoes() {
+                assert!(count_of(&dealt, tetromino) > 0, "{} never dealt {:?}", kind, tetromino);
+            }
+        }
+    }
+}
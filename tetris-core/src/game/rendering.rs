@@ -0,0 +1,14 @@
+use crate::game::Game;
+
+pub trait Renderer: Send + Sync {
+    fn render(&self, game: &Game);
+    fn prerender(&self, game: &Game);
+}
+
+/// Renderer drawing nothing, for bots and tests running games without a screen.
+pub struct NullRenderer;
+
+impl Renderer for NullRenderer {
+    fn render(&self, _game: &Game) {}
+    fn prerender(&self, _game: &Game) {}
+}
@@ -0,0 +1,325 @@
+use super::codec::{write_inputs, write_rules, write_varint, Reader};
+use super::input::Input;
+use super::rendering::{NullRenderer, Renderer};
+use super::rules::Rules;
+use super::{Game, FRAMES_PER_SECOND, FRAME_DURATION};
+
+/// First bytes of every replay file.
+const MAGIC: &[u8; 4] = b"TCGR";
+/// Version of the replay format written by this build. Older versions are rejected.
+pub const REPLAY_VERSION: u8 = 4;
+/// Most frames a replay may go without an input, an hour of play. Games that cannot end would
+/// otherwise keep a replay with made up frames running for good.
+const MAX_IDLE_FRAMES: u64 = 60 * 60 * FRAMES_PER_SECOND;
+
+/// Where a game stood when its recording ended, to check a replay against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayResult {
+    pub score: u64,
+    pub lines: u32,
+    pub level: u8,
+    pub frames: u64,
+}
+
+impl ReplayResult {
+    pub fn of(game: &Game) -> Self {
+        ReplayResult {
+            score: game.score,
+            lines: game.lines,
+            level: game.level,
+            frames: game.get_frame(),
+        }
+    }
+}
+
+/// Everything needed to play a game again exactly as it happened:
+/// its seed, its rules and every input with the frame it was applied on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: Rules,
+    /// Inputs in the order they were applied, with the frame each was applied on.
+    pub inputs: Vec<(u64, Input)>,
+    pub result: ReplayResult,
+}
+
+impl Replay {
+    /// Encodes the replay in the compact binary replay format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64 + self.inputs.len() * 2);
+        out.extend_from_slice(MAGIC);
+        out.push(REPLAY_VERSION);
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        write_rules(&mut out, &self.rules);
+
+        write_varint(&mut out, self.result.score);
+        write_varint(&mut out, self.result.lines as u64);
+        out.push(self.result.level);
+        write_varint(&mut out, self.result.frames);
+
+        write_inputs(&mut out, &self.inputs);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if !bytes.starts_with(MAGIC) {
+            return Err("Not a replay file".to_string());
+        }
+        let mut reader = Reader::new(bytes, MAGIC.len());
+
+        let version = reader.byte()?;
+        if version != REPLAY_VERSION {
+            return Err(format!(
+                "Unsupported replay version {}, this build reads version {}",
+                version, REPLAY_VERSION
+            ));
+        }
+
+        let seed = u64::from_le_bytes(reader.array()?);
+        let rules = reader.rules()?;
+        rules
+            .validate()
+            .map_err(|error| format!("The rules of the replay are invalid: {}", error))?;
+        let result = ReplayResult {
+            score: reader.varint()?,
+            lines: reader.varint_as()?,
+            level: reader.byte()?,
+            frames: reader.varint()?,
+        };
+        let inputs = reader.inputs()?;
+
+        if !reader.is_at_end() {
+            return Err("Unexpected data after the end of the replay".to_string());
+        }
+        let mut last_frame = 0;
+        for frame in inputs.iter().map(|&(frame, _)| frame).chain([result.frames]) {
+            if frame.saturating_sub(last_frame) > MAX_IDLE_FRAMES {
+                let frames = frame - last_frame;
+                return Err(format!("The replay goes {} frames without an input", frames));
+            }
+            last_frame = last_frame.max(frame);
+        }
+
+        Ok(Replay {
+            seed,
+            rules,
+            inputs,
+            result,
+        })
+    }
+
+    /// Plays the replay again without drawing anything,
+    /// checking that it ends where the recording did.
+    pub fn verify(&self) -> Result<ReplayResult, String> {
+        let mut player = ReplayPlayer::new(self.clone());
+        let mut game = player.start(Box::new(NullRenderer));
+        while !player.is_finished(&game) {
+            player.step(&mut game);
+        }
+
+        let result = ReplayResult::of(&game);
+        if result == self.result {
+            Ok(result)
+        } else {
+            Err(format!(
+                "Replay ended with score {}, {} lines and level {} after {} frames, \
+                 but the recording ended with score {}, {} lines and level {} after {} frames",
+                result.score,
+                result.lines,
+                result.level,
+                result.frames,
+                self.result.score,
+                self.result.lines,
+                self.result.level,
+                self.result.frames
+            ))
+        }
+    }
+}
+
+/// Plays a replay frame by frame, applying each recorded input on the frame it was recorded on.
+pub struct ReplayPlayer {
+    replay: Replay,
+    next_input: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        ReplayPlayer {
+            replay,
+            next_input: 0,
+        }
+    }
+
+    pub fn get_replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Starts the recorded game, unpaused and on its first frame.
+    pub fn start(&mut self, renderer: Box<dyn Renderer>) -> Game {
+        self.next_input = 0;
+        let mut game = Game::new(renderer, self.replay.rules.clone(), self.replay.seed);
+        game.set_paused(false);
+        game
+    }
+
+    /// Applies the inputs recorded on the current frame, then plays the frame
+    /// unless the recording ends on it.
+    pub fn step(&mut self, game: &mut Game) {
+        while let Some(input) = self.pending_input(game) {
+            game.apply(input);
+            self.next_input += 1;
+        }
+
+        if game.get_frame() < self.replay.result.frames {
+            game.update(FRAME_DURATION);
+        }
+    }
+
+    /// Whether the game reached the end of the recording.
+    pub fn is_finished(&self, game: &Game) -> bool {
+        game.is_game_over()
+            || (game.get_frame() >= self.replay.result.frames && self.pending_input(game).is_none())
+    }
+
+    /// Next recorded input, if it is due on the current frame.
+    fn pending_input(&self, game: &Game) -> Option<Input> {
+        self.replay
+            .inputs
+            .get(self.next_input)
+            .filter(|(frame, _)| *frame <= game.get_frame())
+            .map(|&(_, input)| input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::consts::field::{MAX_FIELD_WIDTH, MIN_FIELD_WIDTH};
+    use crate::game::gravity::GravityKind;
+    use crate::game::mode::ModeKind;
+    use crate::game::piece_set::PieceSet;
+    use crate::game::randomizer::RandomizerKind;
+
+    /// Plays a short game by hand, dropping pieces at different spots.
+    fn record_game() -> Replay {
+        let mut game = Game::new(Box::new(NullRenderer), Rules::default(), 99);
+        game.set_paused(false);
+
+        let rotations = [Input::RotateCw, Input::RotateCcw, Input::Rotate180];
+        for i in 0..30 {
+            game.apply(rotations[i % rotations.len()]);
+            for _ in 0..5 {
+                game.apply(Input::MoveLeft);
+            }
+            for _ in 0..i * 3 % 10 {
+                game.apply(Input::MoveRight);
+            }
+            game.update(FRAME_DURATION * (i as u32 % 7));
+            game.apply(Input::SoftDrop);
+            if i % 9 == 0 {
+                game.apply(Input::Hold);
+            }
+            game.update(FRAME_DURATION * 3);
+            game.apply(Input::HardDrop);
+        }
+        game.update(std::time::Duration::from_secs(2));
+
+        game.replay()
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut replay = record_game();
+        replay.rules.randomizer = RandomizerKind::Tgm;
+        replay.rules.gravity = GravityKind::Nes;
+        replay.rules.start_level = 7;
+        replay.rules.mode = ModeKind::Sprint;
+        replay.rules.field_width = 20;
+        replay.rules.field_height = 40;
+        replay.rules.field_buffer = 0;
+        replay.rules.pieces = PieceSet::builtin("pentomino").unwrap();
+
+        let bytes = replay.to_bytes();
+        assert_eq!(Replay::from_bytes(&bytes), Ok(replay.clone()));
+
+        // Each input takes about two bytes
+        assert!(bytes.len() < 64 + replay.inputs.len() * 3);
+    }
+
+    #[test]
+    fn test_verify_reproduces_result() {
+        let replay = record_game();
+        assert!(replay.result.score > 0);
+        assert!(replay.inputs.len() > 100);
+
+        assert_eq!(replay.verify(), Ok(replay.result));
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let mut replay = record_game();
+        replay.result.score += 1;
+        assert!(replay.verify().is_err());
+
+        let mut replay = record_game();
+        replay.inputs.retain(|&(_, input)| input != Input::Hold);
+        assert!(replay.verify().is_err());
+    }
+
+    #[test]
+    fn test_rejects_broken_files() {
+        let bytes = record_game().to_bytes();
+
+        assert!(Replay::from_bytes(b"hello").is_err());
+        assert!(Replay::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut newer = bytes.clone();
+        newer[MAGIC.len()] = REPLAY_VERSION + 1;
+        let error = Replay::from_bytes(&newer).unwrap_err();
+        assert!(error.contains("version"));
+    }
+
+    #[test]
+    fn test_rejects_endless_recordings() {
+        // A Zen game never ends by itself, so verifying would never finish
+        let mut replay = record_game();
+        replay.rules.mode = ModeKind::Zen;
+        replay.result.frames = u64::MAX;
+        let error = Replay::from_bytes(&replay.to_bytes()).unwrap_err();
+        assert!(error.contains("without an input"), "{}", error);
+
+        let mut replay = record_game();
+        replay.rules.mode = ModeKind::Zen;
+        replay.inputs.push((u64::MAX / 2, Input::HardDrop));
+        replay.result.frames = u64::MAX / 2;
+        assert!(Replay::from_bytes(&replay.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_rules_out_of_range() {
+        // The game would play on a field brought into range, not the one recorded
+        let mut replay = record_game();
+        replay.rules.field_width = MAX_FIELD_WIDTH + 1;
+        let error = Replay::from_bytes(&replay.to_bytes()).unwrap_err();
+        assert!(error.contains("Field width"), "{}", error);
+
+        let mut replay = record_game();
+        replay.rules.pieces = PieceSet::builtin("pentomino").unwrap();
+        replay.rules.field_width = MIN_FIELD_WIDTH;
+        assert!(Replay::from_bytes(&replay.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_player_stops_at_end_of_recording() {
+        let replay = record_game();
+        let mut player = ReplayPlayer::new(replay.clone());
+        let mut game = player.start(Box::new(NullRenderer));
+
+        while !player.is_finished(&game) {
+            player.step(&mut game);
+        }
+        assert_eq!(game.get_frame(), replay.result.frames);
+        assert_eq!(game.score, replay.result.score);
+    }
+}
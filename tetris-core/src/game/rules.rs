@@ -0,0 +1,93 @@
+use super::consts::field::{
+    FIELD_BUFFER, FIELD_HEIGHT, FIELD_WIDTH, MAX_FIELD_BUFFER, MAX_FIELD_HEIGHT, MAX_FIELD_WIDTH,
+    MIN_FIELD_HEIGHT, MIN_FIELD_WIDTH,
+};
+use super::gravity::GravityKind;
+use super::mode::ModeKind;
+use super::piece_set::PieceSet;
+use super::randomizer::RandomizerKind;
+use std::sync::Arc;
+
+/// Most pieces the preview queue can show.
+pub const MAX_PREVIEW_SIZE: usize = 6;
+
+/// Tunable rules of a game, fixed for its whole duration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    /// Points taken from the score every time the current object is swapped into the hold slot.
+    pub hold_penalty: u64,
+    /// Pieces the game is played with.
+    pub pieces: Arc<PieceSet>,
+    /// Strategy picking the tetrominoes to deal.
+    pub randomizer: RandomizerKind,
+    /// Milliseconds a landed object may still be moved before it locks in place.
+    pub lock_delay_ms: i64,
+    /// How often moving or rotating a landed object restarts its lock delay.
+    /// Reaching a new lowest row gives all resets back.
+    pub max_lock_resets: u8,
+    /// Curve deciding how fast the current object falls on each level.
+    pub gravity: GravityKind,
+    /// Level every game starts on.
+    pub start_level: u8,
+    /// Lines to clear for moving up a level.
+    pub lines_per_level: u32,
+    /// Upcoming pieces shown in the preview queue, from 1 to `MAX_PREVIEW_SIZE`.
+    pub preview_size: usize,
+    /// Columns of the field, from `MIN_FIELD_WIDTH` to `MAX_FIELD_WIDTH`.
+    pub field_width: u16,
+    /// Visible rows of the field, from `MIN_FIELD_HEIGHT` to `MAX_FIELD_HEIGHT`.
+    pub field_height: u16,
+    /// Hidden rows above the visible ones the pieces spawn in, up to `MAX_FIELD_BUFFER`.
+    pub field_buffer: u16,
+    /// Mode deciding the goal of the game.
+    pub mode: ModeKind,
+}
+
+impl Rules {
+    /// Checks rules read from a file, which a game would otherwise quietly bring into range.
+    pub fn validate(&self) -> Result<(), String> {
+        let min_size = self.pieces.get_max_size();
+        if !(1..=MAX_PREVIEW_SIZE).contains(&self.preview_size) {
+            return Err(format!("Preview size {} is out of range", self.preview_size));
+        }
+        if self.lines_per_level == 0 {
+            return Err("Levels take no lines to clear".to_string());
+        }
+        if !(MIN_FIELD_WIDTH..=MAX_FIELD_WIDTH).contains(&self.field_width) {
+            return Err(format!("Field width {} is out of range", self.field_width));
+        }
+        if !(MIN_FIELD_HEIGHT..=MAX_FIELD_HEIGHT).contains(&self.field_height) {
+            return Err(format!("Field height {} is out of range", self.field_height));
+        }
+        if self.field_buffer > MAX_FIELD_BUFFER {
+            return Err(format!("Field buffer {} is out of range", self.field_buffer));
+        }
+        if self.field_width < min_size || self.field_height + self.field_buffer < min_size {
+            return Err(format!(
+                "A field of {}x{} with a buffer of {} is too small for the {} pieces",
+                self.field_width, self.field_height, self.field_buffer, self.pieces
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            hold_penalty: 50,
+            pieces: PieceSet::standard(),
+            randomizer: RandomizerKind::Weighted,
+            lock_delay_ms: 500,
+            max_lock_resets: 15,
+            gravity: GravityKind::Guideline,
+            start_level: 1,
+            lines_per_level: 10,
+            preview_size: 5,
+            field_width: FIELD_WIDTH,
+            field_height: FIELD_HEIGHT,
+            field_buffer: FIELD_BUFFER,
+            mode: ModeKind::default(),
+        }
+    }
+}
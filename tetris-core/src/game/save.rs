@@ -0,0 +1,505 @@
+use super::codec::{
+    direction_to_code, tetromino_to_code, write_bytes, write_inputs, write_rules, write_varint,
+    Reader,
+};
+use super::consts::field::Row;
+use super::field::Field;
+use super::geometry::Pos;
+use super::object::Object;
+use super::piece_set::PieceSet;
+use super::rendering::Renderer;
+use super::scoring::{LineClear, Scoring, TSpin};
+use super::{Game, GameRng, FRAME_DURATION};
+use rand::SeedableRng;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// First bytes of every saved game.
+const MAGIC: &[u8; 4] = b"TCGS";
+/// Version of the save format written by this build. Older versions are rejected.
+pub const SAVE_VERSION: u8 = 6;
+
+impl Game {
+    /// Encodes everything needed to pick the game up later exactly where it is now,
+    /// including its random number generator, so the same pieces keep coming.
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(256 + self.inputs.len() * 2);
+        out.extend_from_slice(MAGIC);
+        out.push(SAVE_VERSION);
+
+        out.extend_from_slice(&self.seed.to_le_bytes());
+        out.extend_from_slice(&self.rng.get_seed());
+        out.extend_from_slice(&self.rng.get_stream().to_le_bytes());
+        out.extend_from_slice(&self.rng.get_word_pos().to_le_bytes());
+        write_rules(&mut out, &self.rules);
+        write_bytes(&mut out, &self.randomizer.save_state());
+
+        write_varint(&mut out, self.field.area.len() as u64);
+        for &row in &self.field.area {
+            write_varint(&mut out, row);
+        }
+
+        write_object(&mut out, &self.current_object);
+        write_varint(&mut out, self.next_objects.len() as u64);
+        for &(tetromino, direction) in &self.next_objects {
+            out.push(tetromino_to_code(tetromino));
+            out.push(direction_to_code(direction));
+        }
+        match self.held_object {
+            Some(tetromino) => out.extend_from_slice(&[1, tetromino_to_code(tetromino)]),
+            None => out.push(0),
+        }
+        out.push(self.hold_used as u8);
+
+        write_varint(&mut out, self.frame);
+        write_varint(&mut out, self.frame_remainder.as_nanos() as u64);
+        write_time(&mut out, self.lock_started_at);
+        out.push(self.lock_resets);
+        write_varint(&mut out, self.lowest_row as u64);
+        out.push(direction_to_code(self.spawned_at.0));
+        write_varint(&mut out, self.spawned_at.1.x as u64);
+        write_varint(&mut out, self.spawned_at.1.y as u64);
+        write_varint(&mut out, self.piece_inputs as u64);
+        match self.last_rotation_kick {
+            Some((x, y)) => out.extend_from_slice(&[1, x as u8, y as u8]),
+            None => out.push(0),
+        }
+        write_time(&mut out, Some(self.last_fall_at));
+
+        let (combo, back_to_back) = self.scoring.get_state();
+        write_varint(&mut out, combo.map_or(0, |combo| combo as u64 + 1));
+        out.push(back_to_back as u8);
+        match self.last_clear {
+            Some(clear) => {
+                out.push(1);
+                write_line_clear(&mut out, &clear);
+            }
+            None => out.push(0),
+        }
+        write_time(&mut out, Some(self.last_clear_at));
+        write_varint(&mut out, self.last_stack_clear.map_or(0, |rows| rows as u64 + 1));
+        write_time(&mut out, Some(self.last_stack_clear_at));
+
+        out.push(self.level);
+        write_varint(&mut out, self.lines as u64);
+        write_varint(&mut out, self.score);
+        write_varint(&mut out, self.garbage_rows as u64);
+        write_varint(&mut out, self.garbage_cleared as u64);
+        write_varint(&mut out, self.pieces as u64);
+        write_varint(&mut out, self.finesse_faults as u64);
+        write_inputs(&mut out, &self.inputs);
+
+        out
+    }
+
+    /// Picks up a game saved by `save_state`, paused. Saves that do not describe a game that
+    /// could have been played, such as one with the current piece stuck in the stack, are refused.
+    pub fn load_state(bytes: &[u8], renderer: Box<dyn Renderer>) -> Result<Game, String> {
+        if !bytes.starts_with(MAGIC) {
+            return Err("Not a saved game".to_string());
+        }
+        let mut reader = Reader::new(bytes, MAGIC.len());
+
+        let version = reader.byte()?;
+        if version != SAVE_VERSION {
+            return Err(format!(
+                "Unsupported save version {}, this build reads version {}",
+                version, SAVE_VERSION
+            ));
+        }
+
+        let seed = u64::from_le_bytes(reader.array()?);
+        let mut rng = GameRng::from_seed(reader.array()?);
+        rng.set_stream(u64::from_le_bytes(reader.array()?));
+        rng.set_word_pos(u128::from_le_bytes(reader.array()?));
+
+        let rules = reader.rules()?;
+        rules
+            .validate()
+            .map_err(|error| format!("The rules of the saved game are invalid: {}", error))?;
+        let mut game = Game::new(renderer, rules, seed);
+        game.rng = rng;
+        game.randomizer.load_state(reader.bytes()?)?;
+
+        read_field(&mut reader, &mut game.field)?;
+
+        let pieces = Arc::clone(&game.rules.pieces);
+        game.current_object = read_object(&mut reader, &pieces, &game.field)?;
+        if !game
+            .field
+            .can_hold(game.current_object.get_qube_with_width(), &game.current_object.pos)
+        {
+            return Err("The current piece overlaps the stack".to_string());
+        }
+
+        let next_count: usize = reader.varint_as()?;
+        if next_count != game.rules.preview_size {
+            return Err("The preview queue does not match the rules".to_string());
+        }
+        game.next_objects = (0..next_count)
+            .map(|_| Ok((reader.tetromino(&pieces)?, reader.direction()?)))
+            .collect::<Result<VecDeque<_>, String>>()?;
+        game.held_object = match reader.bool()? {
+            true => Some(reader.tetromino(&pieces)?),
+            false => None,
+        };
+        game.hold_used = reader.bool()?;
+
+        game.frame = reader.varint()?;
+        game.frame_remainder = Duration::from_nanos(reader.varint()?);
+        game.lock_started_at = read_time(&mut reader)?;
+        game.lock_resets = reader.byte()?;
+        game.lowest_row = reader.varint_as()?;
+        let spawn_direction = reader.direction()?;
+        game.spawned_at = (spawn_direction, read_pos(&mut reader, &game.field)?);
+        game.piece_inputs = reader.varint_as()?;
+        game.last_rotation_kick = match reader.bool()? {
+            true => Some((reader.byte()? as i8, reader.byte()? as i8)),
+            false => None,
+        };
+        game.last_fall_at = read_time(&mut reader)?.ok_or("Missing gravity timer")?;
+
+        let combo = match reader.varint()? {
+            0 => None,
+            combo => Some(u32::try_from(combo - 1).map_err(|_| "Combo is out of range")?),
+        };
+        game.scoring = Scoring::from_state(combo, reader.bool()?);
+        game.last_clear = match reader.bool()? {
+            true => Some(read_line_clear(&mut reader)?),
+            false => None,
+        };
+        game.last_clear_at = read_time(&mut reader)?.ok_or("Missing line clear timer")?;
+        game.last_stack_clear = match reader.varint()? {
+            0 => None,
+            rows => Some(u16::try_from(rows - 1).map_err(|_| "Cleared rows are out of range")?),
+        };
+        game.last_stack_clear_at =
+            read_time(&mut reader)?.ok_or("Missing stack clear timer")?;
+
+        game.level = reader.byte()?;
+        game.lines = reader.varint_as()?;
+        game.score = reader.varint()?;
+        game.garbage_rows = reader.varint_as()?;
+        game.garbage_cleared = reader.varint_as()?;
+        game.pieces = reader.varint_as()?;
+        game.finesse_faults = reader.varint_as()?;
+        game.inputs = reader.inputs()?;
+
+        if !reader.is_at_end() {
+            return Err("Unexpected data after the end of the saved game".to_string());
+        }
+
+        game.validate()?;
+        game.renderer.prerender(&game);
+        game.renderer.render(&game);
+        Ok(game)
+    }
+
+    /// Checks that the timers and counters of a loaded game agree with each other.
+    fn validate(&self) -> Result<(), String> {
+        let time = self.time_ms();
+        let timers = [
+            self.lock_started_at.unwrap_or(0),
+            self.last_fall_at,
+            self.last_clear_at,
+            self.last_stack_clear_at,
+        ];
+        if timers.iter().any(|&timer| timer > time) || self.frame_remainder >= FRAME_DURATION {
+            return Err("The timers of the saved game are ahead of its time".to_string());
+        }
+        if self.inputs.last().is_some_and(|&(frame, _)| frame > self.frame) {
+            return Err("The inputs of the saved game are ahead of its time".to_string());
+        }
+        if self.lock_resets > self.rules.max_lock_resets {
+            return Err("The current piece was moved more often than the lock delay allows".into());
+        }
+        if self.lowest_row < self.current_object.pos.y {
+            return Err("The current piece is below the lowest row it reached".to_string());
+        }
+
+        if self.finesse_faults > self.pieces {
+            return Err("The saved game has more finesse faults than pieces".to_string());
+        }
+        if self.garbage_rows > self.field.height {
+            return Err("The saved game has more garbage rows than the field".to_string());
+        }
+
+        let level = self.mode.level(&self.rules, self.lines);
+        if self.level != level {
+            return Err(format!(
+                "Level {} does not match {} cleared lines",
+                self.level, self.lines
+            ));
+        }
+        if self.mode.is_goal_reached(self) {
+            return Err("The saved game already reached the goal of its mode".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+fn write_object(out: &mut Vec<u8>, object: &Object) {
+    out.push(tetromino_to_code(object.tetromino));
+    out.push(direction_to_code(object.direction));
+    write_varint(out, object.pos.x as u64);
+    write_varint(out, object.pos.y as u64);
+}
+
+fn read_object(reader: &mut Reader, pieces: &PieceSet, field: &Field) -> Result<Object, String> {
+    let tetromino = reader.tetromino(pieces)?;
+    let direction = reader.direction()?;
+    Ok(Object::new(pieces, tetromino, direction, read_pos(reader, field)?))
+}
+
+/// Reads a position, which lies within the field or at most on its right or bottom edge.
+fn read_pos(reader: &mut Reader, field: &Field) -> Result<Pos, String> {
+    let mut pos = Pos::zero();
+    pos.x = reader.varint_as()?;
+    pos.y = reader.varint_as()?;
+    if pos.x > field.width || pos.y > field.rows() {
+        return Err("The saved position is outside the field".to_string());
+    }
+    Ok(pos)
+}
+
+/// Writes a point in game time that may not be set, which is never before the game started.
+fn write_time(out: &mut Vec<u8>, time: Option<i64>) {
+    write_varint(out, time.map_or(0, |time| time.max(0) as u64 + 1));
+}
+
+fn read_time(reader: &mut Reader) -> Result<Option<i64>, String> {
+    match reader.varint()? {
+        0 => Ok(None),
+        time => Ok(Some(i64::try_from(time - 1).map_err(|_| "Time is out of range")?)),
+    }
+}
+
+/// Reads the rows into the field, which has the size the rules of the saved game ask for.
+fn read_field(reader: &mut Reader, field: &mut Field) -> Result<(), String> {
+    let empty_row = field.empty_row();
+    let height: usize = reader.varint_as()?;
+    if height != field.area.len() {
+        return Err(format!(
+            "The saved field has {} rows instead of {}",
+            height,
+            field.area.len()
+        ));
+    }
+
+    for row in field.area.iter_mut() {
+        *row = reader.varint_as()?;
+        // The bits outside of the field are always set, and full rows are always cleared
+        if *row & empty_row != empty_row || *row == Row::MAX {
+            return Err("The saved field holds an invalid row".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn write_line_clear(out: &mut Vec<u8>, clear: &LineClear) {
+    write_varint(out, clear.lines as u64);
+    out.push(match clear.t_spin {
+        TSpin::None => 0,
+        TSpin::Mini => 1,
+        TSpin::Full => 2,
+    });
+    out.push(clear.back_to_back as u8);
+    write_varint(out, clear.combo as u64);
+    out.push(clear.perfect_clear as u8);
+    write_varint(out, clear.points);
+}
+
+fn read_line_clear(reader: &mut Reader) -> Result<LineClear, String> {
+    Ok(LineClear {
+        lines: reader.varint_as()?,
+        t_spin: match reader.byte()? {
+            0 => TSpin::None,
+            1 => TSpin::Mini,
+            2 => TSpin::Full,
+            code => return Err(format!("Unknown T-spin {}", code)),
+        },
+        back_to_back: reader.bool()?,
+        combo: reader.varint_as()?,
+        perfect_clear: reader.bool()?,
+        points: reader.varint()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::consts::field::{MAX_FIELD_WIDTH, MIN_FIELD_HEIGHT};
+    use crate::game::input::Input;
+    use crate::game::mode::{ModeKind, SPRINT_LINES};
+    use crate::game::randomizer::RandomizerKind;
+    use crate::game::rendering::NullRenderer;
+    use crate::game::rules::Rules;
+
+    /// Plays a few pieces, leaving the game in the middle of a piece.
+    fn play(game: &mut Game) {
+        game.set_paused(false);
+        for i in 0..12 {
+            for _ in 0..i % 4 {
+                game.apply(Input::MoveLeft);
+            }
+            if i % 3 == 0 {
+                game.apply(Input::RotateCw);
+            }
+            if i == 5 {
+                game.apply(Input::Hold);
+            }
+            game.update(FRAME_DURATION * 20);
+            game.apply(Input::HardDrop);
+        }
+        game.update(FRAME_DURATION * 25 + FRAME_DURATION / 2);
+        game.apply(Input::MoveRight);
+    }
+
+    fn new_game(randomizer: RandomizerKind) -> Game {
+        let rules = Rules {
+            randomizer,
+            ..Rules::default()
+        };
+        Game::new(Box::new(NullRenderer), rules, 2024)
+    }
+
+    #[test]
+    fn test_loaded_game_plays_on_the_same() {
+        for randomizer in RandomizerKind::ALL {
+            let mut game = new_game(randomizer);
+            play(&mut game);
+            assert!(!game.is_game_over());
+
+            let mut loaded = Game::load_state(&game.save_state(), Box::new(NullRenderer)).unwrap();
+            assert!(loaded.is_paused());
+            assert_eq!(loaded.field.area, game.field.area);
+            assert_eq!(loaded.save_state(), game.save_state());
+
+            loaded.set_paused(false);
+            play(&mut game);
+            play(&mut loaded);
+            assert_eq!(loaded.field.area, game.field.area, "{} randomizer", randomizer);
+            assert_eq!(loaded.score, game.score);
+            assert_eq!(loaded.next_objects, game.next_objects);
+            assert_eq!(loaded.replay(), game.replay());
+        }
+    }
+
+    #[test]
+    fn test_loaded_dig_keeps_its_garbage() {
+        let rules = Rules {
+            mode: ModeKind::Dig,
+            ..Rules::default()
+        };
+        let mut game = Game::new(Box::new(NullRenderer), rules, 2024);
+        game.set_paused(false);
+        game.apply(Input::HardDrop);
+        game.update(FRAME_DURATION * 10);
+        game.garbage_cleared = 3;
+
+        let loaded = Game::load_state(&game.save_state(), Box::new(NullRenderer)).unwrap();
+        assert_eq!(loaded.get_garbage_rows(), game.get_garbage_rows());
+        assert_eq!(loaded.get_garbage_cleared(), 3);
+        assert_eq!(loaded.field.area, game.field.area);
+
+        game.garbage_rows = game.field.height + 1;
+        assert!(Game::load_state(&game.save_state(), Box::new(NullRenderer)).is_err());
+    }
+
+    #[test]
+    fn test_loaded_game_keeps_its_field_size() {
+        let rules = Rules {
+            field_width: 20,
+            field_height: 40,
+            field_buffer: 4,
+            ..Rules::default()
+        };
+        let mut game = Game::new(Box::new(NullRenderer), rules, 2024);
+        play(&mut game);
+
+        let loaded = Game::load_state(&game.save_state(), Box::new(NullRenderer)).unwrap();
+        assert_eq!((loaded.field.width, loaded.field.rows()), (20, 44));
+        assert_eq!(loaded.field.area, game.field.area);
+
+        game.rules.field_width = MAX_FIELD_WIDTH + 1;
+        assert!(Game::load_state(&game.save_state(), Box::new(NullRenderer)).is_err());
+
+        // A field too low for the pieces is rejected rather than crashing
+        game.rules = Rules {
+            pieces: PieceSet::builtin("pentomino").unwrap(),
+            field_height: MIN_FIELD_HEIGHT,
+            field_buffer: 0,
+            ..Rules::default()
+        };
+        assert!(Game::load_state(&game.save_state(), Box::new(NullRenderer)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_overlapping_piece() {
+        let mut game = new_game(RandomizerKind::SevenBag);
+        play(&mut game);
+
+        // Fill the rows of the current piece, leaving one gap so they are not full
+        let y = game.current_object.pos.y as usize;
+        for row in &mut game.field.area[y..y + 2] {
+            *row = Row::MAX - 1;
+        }
+        let Err(error) = Game::load_state(&game.save_state(), Box::new(NullRenderer)) else {
+            panic!("The overlapping piece was accepted");
+        };
+        assert!(error.contains("overlaps"), "{}", error);
+    }
+
+    #[test]
+    fn test_rejects_positions_outside_the_field() {
+        let mut game = new_game(RandomizerKind::SevenBag);
+        play(&mut game);
+        game.current_object.pos.x = u16::MAX;
+        let Err(error) = Game::load_state(&game.save_state(), Box::new(NullRenderer)) else {
+            panic!("The piece outside the field was accepted");
+        };
+        assert!(error.contains("outside the field"), "{}", error);
+
+        let mut game = new_game(RandomizerKind::SevenBag);
+        play(&mut game);
+        game.spawned_at.1.y = game.field.rows() + 1;
+        assert!(Game::load_state(&game.save_state(), Box::new(NullRenderer)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_state() {
+        let mut game = new_game(RandomizerKind::Weighted);
+        play(&mut game);
+        game.level += 1;
+        assert!(Game::load_state(&game.save_state(), Box::new(NullRenderer)).is_err());
+
+        let mut game = new_game(RandomizerKind::Weighted);
+        play(&mut game);
+        game.last_fall_at = game.time_ms() + 1;
+        assert!(Game::load_state(&game.save_state(), Box::new(NullRenderer)).is_err());
+
+        // A Sprint that cleared its lines is over, not waiting to be continued
+        let mut game = new_game(RandomizerKind::Weighted);
+        game.rules.mode = ModeKind::Sprint;
+        game.lines = SPRINT_LINES;
+        assert!(Game::load_state(&game.save_state(), Box::new(NullRenderer)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_broken_files() {
+        let mut game = new_game(RandomizerKind::Tgm);
+        play(&mut game);
+        let bytes = game.save_state();
+
+        assert!(Game::load_state(b"TCGR", Box::new(NullRenderer)).is_err());
+        for len in [5, bytes.len() / 2, bytes.len() - 1] {
+            assert!(Game::load_state(&bytes[..len], Box::new(NullRenderer)).is_err());
+        }
+
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(Game::load_state(&longer, Box::new(NullRenderer)).is_err());
+    }
+}
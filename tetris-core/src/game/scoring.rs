@@ -0,0 +1,245 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TSpin {
+    None,
+    /// Only one of the corners the T points at is blocked.
+    Mini,
+    Full,
+}
+
+/// Everything worth telling the player about a locked piece that cleared lines or spun a T.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineClear {
+    pub lines: u16,
+    pub t_spin: TSpin,
+    /// Difficult clear following another difficult clear.
+    pub back_to_back: bool,
+    /// Number of clears in a row before this one.
+    pub combo: u32,
+    /// The clear emptied the whole field.
+    pub perfect_clear: bool,
+    pub points: u64,
+}
+
+impl LineClear {
+    /// Name of the clear, such as "T-SPIN DOUBLE" or "B2B TETRIS".
+    pub fn title(&self) -> String {
+        let lines = match self.lines {
+            0 => "",
+            1 => "SINGLE",
+            2 => "DOUBLE",
+            3 => "TRIPLE",
+            _ => "TETRIS",
+        };
+        let t_spin = match self.t_spin {
+            TSpin::None => "",
+            TSpin::Mini => "T-SPIN MINI",
+            TSpin::Full => "T-SPIN",
+        };
+        let back_to_back = if self.back_to_back { "B2B" } else { "" };
+
+        [back_to_back, t_spin, lines]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Keeps track of combos and back-to-back clears, and scores locked pieces by the guideline.
+#[derive(Debug, Default)]
+pub struct Scoring {
+    /// Clears in a row so far, `None` once a piece locked without clearing anything.
+    combo: Option<u32>,
+    /// Whether the last line clear was a difficult one, a tetris or a T-spin.
+    back_to_back: bool,
+}
+
+impl Scoring {
+    pub fn new() -> Self {
+        Scoring::default()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Scoring::default();
+    }
+
+    /// Restores the combo and back-to-back chain of a saved game.
+    pub fn from_state(combo: Option<u32>, back_to_back: bool) -> Self {
+        Scoring {
+            combo,
+            back_to_back,
+        }
+    }
+
+    /// Clears in a row so far and whether the last one was difficult, for saving a game.
+    pub fn get_state(&self) -> (Option<u32>, bool) {
+        (self.combo, self.back_to_back)
+    }
+
+    /// Scores a locked piece, returning the clear if there is anything to tell the player about.
+    pub fn lock(
+        &mut self,
+        lines: u16,
+        t_spin: TSpin,
+        perfect_clear: bool,
+        level: u8,
+    ) -> Option<LineClear> {
+        if lines == 0 {
+            // A T-spin without lines neither breaks nor starts a back-to-back chain
+            self.combo = None;
+            return (t_spin != TSpin::None).then(|| LineClear {
+                lines,
+                t_spin,
+                back_to_back: false,
+                combo: 0,
+                perfect_clear: false,
+                points: base_points(lines, t_spin) * level as u64,
+            });
+        }
+
+        let difficult = lines >= 4 || t_spin != TSpin::None;
+        let back_to_back = difficult && self.back_to_back;
+        self.back_to_back = difficult;
+
+        let combo = self.combo.map_or(0, |combo| combo + 1);
+        self.combo = Some(combo);
+
+        let mut points = base_points(lines, t_spin);
+        if back_to_back {
+            points = points * 3 / 2;
+        }
+        points += 50 * combo as u64;
+        if perfect_clear {
+            points += perfect_clear_points(lines, back_to_back);
+        }
+
+        Some(LineClear {
+            lines,
+            t_spin,
+            back_to_back,
+            combo,
+            perfect_clear,
+            points: points * level as u64,
+        })
+    }
+}
+
+/// Points at level 1, before any bonus.
+fn base_points(lines: u16, t_spin: TSpin) -> u64 {
+    match (t_spin, lines) {
+        (TSpin::None, 0) => 0,
+        (TSpin::None, 1) => 100,
+        (TSpin::None, 2) => 300,
+        (TSpin::None, 3) => 500,
+        (TSpin::None, _) => 800,
+        (TSpin::Mini, 0) => 100,
+        (TSpin::Mini, 1) => 200,
+        (TSpin::Mini, _) => 400,
+        (TSpin::Full, 0) => 400,
+        (TSpin::Full, 1) => 800,
+        (TSpin::Full, 2) => 1200,
+        (TSpin::Full, _) => 1600,
+    }
+}
+
+fn perfect_clear_points(lines: u16, back_to_back: bool) -> u64 {
+    match lines {
+        1 => 800,
+        2 => 1200,
+        3 => 1800,
+        _ if back_to_back => 3200,
+        _ => 2000,
+    }
+}
+
+/// Points for each row a piece was soft dropped.
+pub const SOFT_DROP_POINTS: u64 = 1;
+/// Points for each row a piece was hard dropped.
+pub const HARD_DROP_POINTS: u64 = 2;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_clears_scale_with_level() {
+        let mut scoring = Scoring::new();
+
+        assert_eq!(scoring.lock(1, TSpin::None, false, 1).unwrap().points, 100);
+        scoring.reset();
+        assert_eq!(scoring.lock(2, TSpin::None, false, 2).unwrap().points, 600);
+        scoring.reset();
+        assert_eq!(scoring.lock(3, TSpin::None, false, 1).unwrap().points, 500);
+        scoring.reset();
+        assert_eq!(scoring.lock(4, TSpin::None, false, 3).unwrap().points, 2400);
+    }
+
+    #[test]
+    fn test_no_clear() {
+        let mut scoring = Scoring::new();
+        assert_eq!(scoring.lock(0, TSpin::None, false, 1), None);
+
+        let clear = scoring.lock(0, TSpin::Full, false, 1).unwrap();
+        assert_eq!(clear.points, 400);
+        assert_eq!(clear.title(), "T-SPIN");
+    }
+
+    #[test]
+    fn test_t_spins() {
+        let mut scoring = Scoring::new();
+
+        let clear = scoring.lock(2, TSpin::Full, false, 1).unwrap();
+        assert_eq!(clear.points, 1200);
+        assert_eq!(clear.title(), "T-SPIN DOUBLE");
+
+        scoring.reset();
+        let clear = scoring.lock(1, TSpin::Mini, false, 1).unwrap();
+        assert_eq!(clear.points, 200);
+        assert_eq!(clear.title(), "T-SPIN MINI SINGLE");
+    }
+
+    #[test]
+    fn test_back_to_back() {
+        let mut scoring = Scoring::new();
+
+        let clear = scoring.lock(4, TSpin::None, false, 1).unwrap();
+        assert!(!clear.back_to_back);
+
+        // A T-spin without lines keeps the chain alive, but breaks the combo
+        scoring.lock(0, TSpin::Full, false, 1);
+        let clear = scoring.lock(4, TSpin::None, false, 1).unwrap();
+        assert!(clear.back_to_back);
+        assert_eq!(clear.points, 1200);
+        assert_eq!(clear.title(), "B2B TETRIS");
+
+        // An easy clear breaks the chain
+        scoring.lock(1, TSpin::None, false, 1);
+        let clear = scoring.lock(2, TSpin::Full, false, 1).unwrap();
+        assert!(!clear.back_to_back);
+    }
+
+    #[test]
+    fn test_combo() {
+        let mut scoring = Scoring::new();
+
+        assert_eq!(scoring.lock(1, TSpin::None, false, 1).unwrap().combo, 0);
+        assert_eq!(scoring.lock(1, TSpin::None, false, 1).unwrap().combo, 1);
+
+        let clear = scoring.lock(1, TSpin::None, false, 2).unwrap();
+        assert_eq!(clear.combo, 2);
+        assert_eq!(clear.points, (100 + 2 * 50) * 2);
+
+        // Locking without a clear ends the combo
+        scoring.lock(0, TSpin::None, false, 1);
+        assert_eq!(scoring.lock(1, TSpin::None, false, 1).unwrap().combo, 0);
+    }
+
+    #[test]
+    fn test_perfect_clear() {
+        let mut scoring = Scoring::new();
+
+        let clear = scoring.lock(4, TSpin::None, true, 1).unwrap();
+        assert!(clear.perfect_clear);
+        assert_eq!(clear.points, 800 + 2000);
+    }
+}
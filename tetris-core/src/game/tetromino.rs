@@ -0,0 +1,36 @@
+/// One of the pieces of a piece set, by its position in the set. Tetrominoes unless the rules
+/// pick another set, which is what the name is left over from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tetromino {
+    index: u8,
+}
+
+impl Tetromino {
+    pub const L: Tetromino = Tetromino::new(0);
+    pub const J: Tetromino = Tetromino::new(1);
+    pub const T: Tetromino = Tetromino::new(2);
+    pub const I: Tetromino = Tetromino::new(3);
+    pub const O: Tetromino = Tetromino::new(4);
+    pub const S: Tetromino = Tetromino::new(5);
+    pub const Z: Tetromino = Tetromino::new(6);
+
+    /// The pieces of the standard set, in its order.
+    pub const ALL: [Tetromino; 7] = [
+        Tetromino::L,
+        Tetromino::J,
+        Tetromino::T,
+        Tetromino::I,
+        Tetromino::O,
+        Tetromino::S,
+        Tetromino::Z,
+    ];
+
+    pub(crate) const fn new(index: u8) -> Self {
+        Tetromino { index }
+    }
+
+    /// Position of the piece in its set, which is how the binary formats store it.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+}
@@ -0,0 +1,5 @@
+//! Headless Tetris engine: the field, pieces, rules and game state.
+//! It knows nothing about terminals or audio. Frontends draw through the `Renderer` trait
+//! and observe everything else that happens as a stream of `GameEvent`s.
+
+pub mod game;